@@ -0,0 +1,121 @@
+// Plays a beginner game through the C API by opening the fields row by row, the events are printed
+// by the registered callbacks instead of querying the fields after every move
+use cminesweeper::*;
+use libc::c_void;
+use minesweeper::{FieldType, FlagResult, Game, GameLevel, LossReason, OpenResult, SizeType};
+use std::ptr;
+
+#[derive(Default)]
+struct Statistics {
+    open_events: usize,
+    game_is_over: bool,
+}
+
+fn get_statistics(user_data: *mut c_void) -> &'static mut Statistics {
+    unsafe { &mut *(user_data as *mut Statistics) }
+}
+
+extern "C" fn on_cell_opened(
+    user_data: *mut c_void,
+    row: SizeType,
+    column: SizeType,
+    field_type: FieldType,
+) {
+    get_statistics(user_data).open_events += 1;
+    println!("Opened ({}, {}): {}", row, column, field_type);
+}
+
+extern "C" fn on_cell_flagged(
+    _user_data: *mut c_void,
+    row: SizeType,
+    column: SizeType,
+    flag_result: FlagResult,
+) {
+    println!("Flag toggled ({}, {}): {}", row, column, flag_result);
+}
+
+extern "C" fn on_game_won(user_data: *mut c_void) {
+    get_statistics(user_data).game_is_over = true;
+    println!("You won!");
+}
+
+extern "C" fn on_game_lost(user_data: *mut c_void, reason: LossReason) {
+    get_statistics(user_data).game_is_over = true;
+    println!("You lost: {}", reason);
+}
+
+extern "C" fn on_timer_tick(_user_data: *mut c_void, elapsed_ms: u64) {
+    println!("Elapsed: {} ms", elapsed_ms);
+}
+
+fn check_error(error_info: &CErrorInfo) {
+    assert_eq!(CError::Ok, error_info.error_code);
+}
+
+fn main() {
+    let mut error_message = vec![0; 100];
+    let mut error_info = CErrorInfo {
+        error_code: CError::Ok,
+        error_message_length: 0,
+        error_message_max_length: error_message.len() as u64,
+        error_message: error_message.as_mut_ptr(),
+    };
+    let mut game_ptr: *mut Game = ptr::null_mut();
+    minesweeper_new_game(&mut game_ptr, GameLevel::Beginner, &mut error_info);
+    check_error(&error_info);
+
+    let mut statistics = Statistics::default();
+    let callbacks = CCallbacks {
+        user_data: &mut statistics as *mut Statistics as *mut c_void,
+        on_cell_opened: Some(on_cell_opened),
+        on_cell_flagged: Some(on_cell_flagged),
+        on_game_won: Some(on_game_won),
+        on_game_lost: Some(on_game_lost),
+        on_timer_tick: Some(on_timer_tick),
+    };
+    minesweeper_game_set_callbacks(game_ptr, &callbacks, &mut error_info);
+    check_error(&error_info);
+
+    let mut flag_result = FlagResult::AlreadyOpened;
+    minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+    check_error(&error_info);
+    minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+    check_error(&error_info);
+
+    let mut width = 0;
+    let mut height = 0;
+    minesweeper_game_get_width(game_ptr, &mut width, &mut error_info);
+    check_error(&error_info);
+    minesweeper_game_get_height(game_ptr, &mut height, &mut error_info);
+    check_error(&error_info);
+
+    let mut buffer = vec![
+        COpenedField {
+            row: 0,
+            column: 0,
+            field_type: FieldType::Empty,
+        };
+        width as usize * height as usize
+    ];
+    'rows: for row in 0..height {
+        for column in 0..width {
+            let mut open_info = COpenInfo {
+                result: OpenResult::Ok,
+                newly_opened_fields_length: 0,
+                newly_opened_fields_max_length: buffer.len() as u64,
+                newly_opened_fields_ptr: buffer.as_mut_ptr(),
+            };
+            minesweeper_game_open(game_ptr, row, column, &mut open_info, &mut error_info);
+            check_error(&error_info);
+            minesweeper_game_update_timer(game_ptr, &mut error_info);
+            check_error(&error_info);
+            if statistics.game_is_over {
+                break 'rows;
+            }
+        }
+    }
+    println!("Number of open events: {}", statistics.open_events);
+
+    minesweeper_destroy_game(&mut game_ptr, &mut error_info);
+    check_error(&error_info);
+}
@@ -0,0 +1,292 @@
+#ifndef CMINESWEEPER_H
+#define CMINESWEEPER_H
+
+/* This file is generated by cbindgen from the cminesweeper crate, do not modify it manually! */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum {
+  C_ERROR_OK,
+  C_ERROR_INVALID_INPUT,
+  C_ERROR_NULL_POINTER_AS_INPUT,
+  C_ERROR_INDEX_IS_OUT_OF_RANGE,
+  C_ERROR_INSUFFICIENT_BUFFER,
+  C_ERROR_UNEXPECTED_ERROR,
+  C_ERROR_TOO_MUCH_MINES,
+  C_ERROR_TOO_FEW_MINES,
+  C_ERROR_TOO_MUCH_FIELDS,
+  C_ERROR_INVALID_HANDLE,
+  C_ERROR_GAME_PAUSED,
+  C_ERROR_GAME_NOT_RUNNING,
+  C_ERROR_GAME_NOT_PAUSED,
+} CError;
+
+typedef enum {
+  FIELD_STATE_CLOSED,
+  FIELD_STATE_OPENED,
+  FIELD_STATE_FLAGGED,
+  FIELD_STATE_VOID,
+} FieldState;
+
+typedef enum {
+  FLAG_RESULT_FLAGGED,
+  FLAG_RESULT_FLAG_REMOVED,
+  FLAG_RESULT_ALREADY_OPENED,
+} FlagResult;
+
+typedef enum {
+  GAME_LEVEL_BEGINNER,
+  GAME_LEVEL_INTERMEDIATE,
+  GAME_LEVEL_EXPERT,
+} GameLevel;
+
+typedef enum {
+  LOSS_REASON_BOOM,
+  LOSS_REASON_TIME_IS_UP,
+  LOSS_REASON_OUT_OF_MOVES,
+} LossReason;
+
+typedef enum {
+  OPEN_RESULT_OK,
+  OPEN_RESULT_IS_FLAGGED,
+  OPEN_RESULT_BOOM,
+  OPEN_RESULT_WINNER,
+  OPEN_RESULT_TIME_IS_UP,
+  OPEN_RESULT_OUT_OF_MOVES,
+} OpenResult;
+
+typedef struct Game Game;
+
+typedef uint64_t ArraySizeType;
+
+typedef struct {
+  CError error_code;
+  ArraySizeType error_message_length;
+  ArraySizeType error_message_max_length;
+  char *error_message;
+} CErrorInfo;
+
+typedef int64_t SizeType;
+
+typedef SizeType GameSizeType;
+
+typedef enum {
+  FIELD_TYPE_EMPTY,
+  FIELD_TYPE_NUMBERED,
+  FIELD_TYPE_MINE,
+} FieldType_Tag;
+
+typedef struct {
+  FieldType_Tag tag;
+  union {
+    struct {
+      uint8_t numbered;
+    };
+  };
+} FieldType;
+
+typedef struct {
+  GameSizeType row;
+  GameSizeType column;
+  FieldType field_type;
+} COpenedField;
+
+/**
+ * If the buffer is too small for the newly opened fields, InsufficientBuffer is returned, the game
+ * is not changed and newly_opened_fields_length contains the required length. The required length
+ * can be queried by passing zero as newly_opened_fields_max_length. The newly_opened_fields_length
+ * must be zero when an open function is called, so it has to be reset before the struct is reused.
+ */
+typedef struct {
+  OpenResult result;
+  ArraySizeType newly_opened_fields_length;
+  ArraySizeType newly_opened_fields_max_length;
+  COpenedField *newly_opened_fields_ptr;
+} COpenInfo;
+
+typedef struct {
+  FieldState state;
+  FieldType field_type;
+} FieldInfo;
+
+typedef void (*CCellOpenedCallback)(void *user_data, GameSizeType, GameSizeType, FieldType);
+
+typedef void (*CCellFlaggedCallback)(void *user_data, GameSizeType, GameSizeType, FlagResult);
+
+typedef void (*CGameWonCallback)(void *user_data);
+
+typedef void (*CGameLostCallback)(void *user_data, LossReason);
+
+typedef void (*CTimerTickCallback)(void *user_data, uint64_t);
+
+typedef struct {
+  void *user_data;
+  CCellOpenedCallback on_cell_opened;
+  CCellFlaggedCallback on_cell_flagged;
+  CGameWonCallback on_game_won;
+  CGameLostCallback on_game_lost;
+  CTimerTickCallback on_timer_tick;
+} CCallbacks;
+
+typedef uint64_t CGameHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+void minesweeper_get_last_error(CErrorInfo *c_ei_ptr);
+
+void minesweeper_new_game(Game **game_ptr_ptr, GameLevel game_level, CErrorInfo *c_ei_ptr);
+
+void minesweeper_new_custom_game(Game **game_ptr_ptr,
+                                 GameSizeType height,
+                                 GameSizeType width,
+                                 GameSizeType number_of_mines,
+                                 CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_open_neighbors(Game *game_ptr,
+                                     GameSizeType row,
+                                     GameSizeType column,
+                                     COpenInfo *c_open_info_ptr,
+                                     CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_open(Game *game_ptr,
+                           GameSizeType row,
+                           GameSizeType column,
+                           COpenInfo *c_open_info_ptr,
+                           CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_toggle_flag(Game *game_ptr,
+                                  GameSizeType row,
+                                  GameSizeType column,
+                                  FlagResult *field_flag_result_ptr,
+                                  CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_pause(Game *game_ptr, CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_resume(Game *game_ptr, CErrorInfo *c_ei_ptr);
+
+void minesweeper_destroy_game(Game **game_ptr_ptr, CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_width(const Game *game_ptr,
+                                GameSizeType *width_ptr,
+                                CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_height(const Game *game_ptr,
+                                 GameSizeType *height_ptr,
+                                 CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_elapsed_seconds(const Game *game_ptr,
+                                          uint64_t *elapsed_seconds_ptr,
+                                          CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_elapsed_milliseconds(const Game *game_ptr,
+                                               uint64_t *elapsed_milliseconds_ptr,
+                                               CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_elapsed_nanoseconds(const Game *game_ptr,
+                                              uint64_t *elapsed_nanoseconds_ptr,
+                                              CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_started_at_nanoseconds(const Game *game_ptr,
+                                                 uint64_t *started_at_nanoseconds_ptr,
+                                                 CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_get_field_info(Game *game_ptr,
+                                     GameSizeType row,
+                                     GameSizeType column,
+                                     FieldInfo *field_info_ptr,
+                                     CErrorInfo *c_ei_ptr);
+
+/**
+ * Passing a null pointer as callbacks removes the previously set callbacks. The callbacks are
+ * called while the game is being modified, so they must not call back into the same game.
+ */
+void minesweeper_game_set_callbacks(Game *game_ptr,
+                                    const CCallbacks *callbacks_ptr,
+                                    CErrorInfo *c_ei_ptr);
+
+void minesweeper_game_update_timer(Game *game_ptr, CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_new_game(GameLevel game_level,
+                                 CGameHandle *handle_ptr,
+                                 CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_new_custom_game(GameSizeType height,
+                                        GameSizeType width,
+                                        GameSizeType number_of_mines,
+                                        CGameHandle *handle_ptr,
+                                        CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_destroy_game(CGameHandle handle, CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_open(CGameHandle handle,
+                                  GameSizeType row,
+                                  GameSizeType column,
+                                  COpenInfo *c_open_info_ptr,
+                                  CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_open_neighbors(CGameHandle handle,
+                                            GameSizeType row,
+                                            GameSizeType column,
+                                            COpenInfo *c_open_info_ptr,
+                                            CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_toggle_flag(CGameHandle handle,
+                                         GameSizeType row,
+                                         GameSizeType column,
+                                         FlagResult *field_flag_result_ptr,
+                                         CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_pause(CGameHandle handle, CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_resume(CGameHandle handle, CErrorInfo *c_ei_ptr);
+
+/**
+ * The callbacks are called while the game is locked, so they must not use the same handle.
+ */
+void minesweeper_handle_game_set_callbacks(CGameHandle handle,
+                                           const CCallbacks *callbacks_ptr,
+                                           CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_update_timer(CGameHandle handle, CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_width(CGameHandle handle,
+                                       GameSizeType *width_ptr,
+                                       CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_height(CGameHandle handle,
+                                        GameSizeType *height_ptr,
+                                        CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_elapsed_seconds(CGameHandle handle,
+                                                 uint64_t *elapsed_seconds_ptr,
+                                                 CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_elapsed_milliseconds(CGameHandle handle,
+                                                      uint64_t *elapsed_milliseconds_ptr,
+                                                      CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_elapsed_nanoseconds(CGameHandle handle,
+                                                     uint64_t *elapsed_nanoseconds_ptr,
+                                                     CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_started_at_nanoseconds(CGameHandle handle,
+                                                        uint64_t *started_at_nanoseconds_ptr,
+                                                        CErrorInfo *c_ei_ptr);
+
+void minesweeper_handle_game_get_field_info(CGameHandle handle,
+                                            GameSizeType row,
+                                            GameSizeType column,
+                                            FieldInfo *field_info_ptr,
+                                            CErrorInfo *c_ei_ptr);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* CMINESWEEPER_H */
@@ -0,0 +1,319 @@
+// Callback based event interface: the registered functions are called synchronously from the game
+// operations, so the caller does not have to poll the fields after every move. When the game is
+// used through a handle, the callbacks are called while the game is locked, therefore they must
+// not use the same handle. When the game is used through its pointer, the callbacks are called
+// while the game is borrowed mutably, therefore they must not call any function with the same game
+// pointer.
+use super::*;
+use libc::c_void;
+use minesweeper::{GameListener, LossReason};
+
+pub type CCellOpenedCallback =
+    Option<extern "C" fn(user_data: *mut c_void, GameSizeType, GameSizeType, FieldType)>;
+pub type CCellFlaggedCallback =
+    Option<extern "C" fn(user_data: *mut c_void, GameSizeType, GameSizeType, FlagResult)>;
+pub type CGameWonCallback = Option<extern "C" fn(user_data: *mut c_void)>;
+pub type CGameLostCallback = Option<extern "C" fn(user_data: *mut c_void, LossReason)>;
+// The elapsed time is passed in milliseconds
+pub type CTimerTickCallback = Option<extern "C" fn(user_data: *mut c_void, u64)>;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct CCallbacks {
+    pub user_data: *mut c_void,
+    pub on_cell_opened: CCellOpenedCallback,
+    pub on_cell_flagged: CCellFlaggedCallback,
+    pub on_game_won: CGameWonCallback,
+    pub on_game_lost: CGameLostCallback,
+    pub on_timer_tick: CTimerTickCallback,
+}
+
+struct CallbackListener {
+    callbacks: CCallbacks,
+}
+
+// The user data is only passed back to the callbacks, it is the responsibility of the caller to
+// make it usable from the thread the game is used on
+unsafe impl Send for CallbackListener {}
+
+impl GameListener for CallbackListener {
+    fn on_field_opened(&mut self, row: GameSizeType, col: GameSizeType, field_type: FieldType) {
+        if let Some(callback) = self.callbacks.on_cell_opened {
+            callback(self.callbacks.user_data, row, col, field_type);
+        }
+    }
+
+    fn on_field_flagged(&mut self, row: GameSizeType, col: GameSizeType, flag_result: FlagResult) {
+        if let Some(callback) = self.callbacks.on_cell_flagged {
+            callback(self.callbacks.user_data, row, col, flag_result);
+        }
+    }
+
+    fn on_game_won(&mut self) {
+        if let Some(callback) = self.callbacks.on_game_won {
+            callback(self.callbacks.user_data);
+        }
+    }
+
+    fn on_game_lost(&mut self, reason: LossReason) {
+        if let Some(callback) = self.callbacks.on_game_lost {
+            callback(self.callbacks.user_data, reason);
+        }
+    }
+
+    fn on_timer_tick(&mut self, elapsed: Duration) {
+        if let Some(callback) = self.callbacks.on_timer_tick {
+            callback(self.callbacks.user_data, elapsed.as_millis() as u64);
+        }
+    }
+}
+
+/// Passing a null pointer as callbacks removes the previously set callbacks. The callbacks are
+/// called while the game is being modified, so they must not call back into the same game.
+#[no_mangle]
+pub extern "C" fn minesweeper_game_set_callbacks(
+    game_ptr: *mut Game,
+    callbacks_ptr: *const CCallbacks,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let listener = unsafe { callbacks_ptr.as_ref() }.map(|callbacks| {
+            Box::new(CallbackListener {
+                callbacks: *callbacks,
+            }) as Box<dyn GameListener>
+        });
+        game.set_listener(listener);
+    });
+}
+
+// Checks the time limit and calls the timer tick callback if a new second is elapsed, it should be
+// called periodically, e.g. from a UI timer
+#[no_mangle]
+pub extern "C" fn minesweeper_game_update_timer(game_ptr: *mut Game, c_ei_ptr: *mut CErrorInfo) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
+        game.update_timer();
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use minesweeper::ManualClock;
+
+    #[derive(Debug, Eq, PartialEq)]
+    enum Event {
+        Opened(GameSizeType, GameSizeType, FieldType),
+        Flagged(GameSizeType, GameSizeType, FlagResult),
+        Won,
+        Lost(LossReason),
+        Tick(u64),
+    }
+
+    extern "C" fn record_opened(
+        user_data: *mut c_void,
+        row: GameSizeType,
+        col: GameSizeType,
+        field_type: FieldType,
+    ) {
+        let events = unsafe { &mut *(user_data as *mut Vec<Event>) };
+        events.push(Event::Opened(row, col, field_type));
+    }
+
+    extern "C" fn record_flagged(
+        user_data: *mut c_void,
+        row: GameSizeType,
+        col: GameSizeType,
+        flag_result: FlagResult,
+    ) {
+        let events = unsafe { &mut *(user_data as *mut Vec<Event>) };
+        events.push(Event::Flagged(row, col, flag_result));
+    }
+
+    extern "C" fn record_won(user_data: *mut c_void) {
+        let events = unsafe { &mut *(user_data as *mut Vec<Event>) };
+        events.push(Event::Won);
+    }
+
+    extern "C" fn record_lost(user_data: *mut c_void, reason: LossReason) {
+        let events = unsafe { &mut *(user_data as *mut Vec<Event>) };
+        events.push(Event::Lost(reason));
+    }
+
+    extern "C" fn record_tick(user_data: *mut c_void, elapsed_ms: u64) {
+        let events = unsafe { &mut *(user_data as *mut Vec<Event>) };
+        events.push(Event::Tick(elapsed_ms));
+    }
+
+    fn create_callbacks(events: &mut Vec<Event>) -> CCallbacks {
+        CCallbacks {
+            user_data: events as *mut Vec<Event> as *mut c_void,
+            on_cell_opened: Some(record_opened),
+            on_cell_flagged: Some(record_flagged),
+            on_game_won: Some(record_won),
+            on_game_lost: Some(record_lost),
+            on_timer_tick: Some(record_tick),
+        }
+    }
+
+    fn create_empty_error_info() -> CErrorInfo {
+        CErrorInfo {
+            error_code: CError::UnexpectedError,
+            error_message_length: 0,
+            error_message_max_length: 0,
+            error_message: ptr::null_mut(),
+        }
+    }
+
+    fn create_open_info(buffer: &mut Vec<COpenedField>) -> COpenInfo {
+        COpenInfo {
+            result: OpenResult::Ok,
+            newly_opened_fields_max_length: buffer.len() as ArraySizeType,
+            newly_opened_fields_length: 0,
+            newly_opened_fields_ptr: buffer.as_mut_ptr(),
+        }
+    }
+
+    fn destroy_game(mut game_ptr: *mut Game) {
+        let mut error_info = create_empty_error_info();
+        minesweeper_destroy_game(&mut game_ptr, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+    }
+
+    #[test]
+    fn won_game_calls_callbacks() {
+        let mut events = Vec::new();
+        let callbacks = create_callbacks(&mut events);
+        let mut error_info = create_empty_error_info();
+        let mut game_ptr: *mut Game = ptr::null_mut();
+        // The first opened field never contains a mine, so opening the middle field wins
+        minesweeper_new_custom_game(&mut game_ptr, 3, 3, 8, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        minesweeper_game_set_callbacks(game_ptr, &callbacks, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        let mut buffer = vec![
+            COpenedField {
+                row: 0,
+                column: 0,
+                field_type: FieldType::Empty,
+            };
+            9
+        ];
+        let mut open_info = create_open_info(&mut buffer);
+        minesweeper_game_open(game_ptr, 1, 1, &mut open_info, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert_eq!(OpenResult::WINNER, open_info.result);
+
+        // Winning reveals the mines too, the order of the opened fields is unspecified
+        assert_eq!(
+            vec![
+                Event::Flagged(0, 0, FlagResult::Flagged),
+                Event::Flagged(0, 0, FlagResult::FlagRemoved),
+            ],
+            events[..2]
+        );
+        assert!(events.contains(&Event::Opened(1, 1, FieldType::Numbered(8))));
+        assert_eq!(
+            8,
+            events
+                .iter()
+                .filter(|event| matches!(event, Event::Opened(_, _, FieldType::Mine)))
+                .count()
+        );
+        assert_eq!(Some(&Event::Won), events.last());
+        assert_eq!(12, events.len());
+        destroy_game(game_ptr);
+    }
+
+    #[test]
+    fn timer_tick_and_time_is_up_call_callbacks() {
+        let clock = ManualClock::new();
+        let mut game = Game::new(GameLevel::Beginner);
+        game.set_clock(Box::new(clock.clone())).unwrap();
+        game.set_time_limit(Some(Duration::from_secs(2))).unwrap();
+        let game_ptr = Box::into_raw(Box::new(game));
+
+        let mut events = Vec::new();
+        let callbacks = create_callbacks(&mut events);
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_set_callbacks(game_ptr, &callbacks, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 2, 3, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        clock.advance(Duration::from_millis(1250));
+        minesweeper_game_update_timer(game_ptr, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        minesweeper_game_update_timer(game_ptr, &mut error_info);
+        clock.advance(Duration::from_secs(5));
+        minesweeper_game_update_timer(game_ptr, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        assert_eq!(
+            vec![
+                Event::Flagged(2, 3, FlagResult::Flagged),
+                Event::Tick(1250),
+                Event::Tick(2000),
+                Event::Lost(LossReason::TimeIsUp),
+            ],
+            events
+        );
+        destroy_game(game_ptr);
+    }
+
+    #[test]
+    fn null_callbacks_remove_callbacks() {
+        let mut events = Vec::new();
+        let callbacks = create_callbacks(&mut events);
+        let game_ptr = Box::into_raw(Box::new(Game::new(GameLevel::Beginner)));
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_set_callbacks(game_ptr, &callbacks, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        minesweeper_game_set_callbacks(game_ptr, ptr::null(), &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert!(events.is_empty());
+        destroy_game(game_ptr);
+    }
+
+    #[test]
+    fn missing_callbacks_are_skipped() {
+        let mut events = Vec::new();
+        let callbacks = CCallbacks {
+            on_cell_flagged: None,
+            ..create_callbacks(&mut events)
+        };
+        let game_ptr = Box::into_raw(Box::new(Game::new(GameLevel::Beginner)));
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_set_callbacks(game_ptr, &callbacks, &mut error_info);
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert!(events.is_empty());
+        destroy_game(game_ptr);
+    }
+
+    #[test]
+    fn null_game() {
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_set_callbacks(ptr::null_mut(), ptr::null(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+        error_info = create_empty_error_info();
+        minesweeper_game_update_timer(ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+    }
+}
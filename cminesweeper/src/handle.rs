@@ -0,0 +1,443 @@
+// Thread-safe alternative of the pointer based API: the games are stored in a registry and they are
+// identified by opaque handles. The handles are never reused, therefore using a destroyed game is
+// detected. Every call locks the game, so a game can be used from multiple threads.
+use super::callbacks::*;
+use super::*;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+static INVALID_HANDLE_ERROR: &str = "Invalid handle!";
+static POISONED_GAME_ERROR: &str = "The game is poisoned, it cannot be used anymore!";
+
+pub type CGameHandle = u64;
+
+struct Registry {
+    next_handle: CGameHandle,
+    games: HashMap<CGameHandle, Arc<Mutex<Game>>>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+        next_handle: 1,
+        games: HashMap::new(),
+    });
+}
+
+fn lock_registry() -> std::sync::MutexGuard<'static, Registry> {
+    // The registry is always left in a consistent state, so the poisoning can be ignored
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn register_game(game: Game) -> CGameHandle {
+    let mut registry = lock_registry();
+    let handle = registry.next_handle;
+    registry.next_handle += 1;
+    registry.games.insert(handle, Arc::new(Mutex::new(game)));
+    handle
+}
+
+fn get_game(handle: CGameHandle) -> Option<Arc<Mutex<Game>>> {
+    lock_registry().games.get(&handle).cloned()
+}
+
+fn with_game(handle: CGameHandle, c_ei_ptr: *mut CErrorInfo, func: impl FnOnce(*mut Game)) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = match get_game(handle) {
+            Some(game) => game,
+            None => {
+                return_error!(c_ei_ptr, CError::InvalidHandle, INVALID_HANDLE_ERROR);
+            }
+        };
+        let mut game = match game.lock() {
+            Ok(game) => game,
+            Err(_) => {
+                return_error!(c_ei_ptr, CError::UnexpectedError, POISONED_GAME_ERROR);
+            }
+        };
+        func(&mut *game);
+    });
+}
+
+fn new_game_common(
+    handle_ptr: *mut CGameHandle,
+    c_ei_ptr: *mut CErrorInfo,
+    new_game_func: impl FnOnce(*mut *mut Game),
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let handle = get_mut_ref_from_ptr!(handle_ptr, c_ei_ptr);
+        let mut game_ptr: *mut Game = ptr::null_mut();
+        new_game_func(&mut game_ptr);
+        if game_ptr.is_null() {
+            return;
+        }
+        let game = unsafe { Box::from_raw(game_ptr) };
+        *handle = register_game(*game);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_new_game(
+    game_level: GameLevel,
+    handle_ptr: *mut CGameHandle,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    new_game_common(handle_ptr, c_ei_ptr, |game_ptr_ptr| {
+        minesweeper_new_game(game_ptr_ptr, game_level, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_new_custom_game(
+    height: GameSizeType,
+    width: GameSizeType,
+    number_of_mines: GameSizeType,
+    handle_ptr: *mut CGameHandle,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    new_game_common(handle_ptr, c_ei_ptr, |game_ptr_ptr| {
+        minesweeper_new_custom_game(game_ptr_ptr, height, width, number_of_mines, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_destroy_game(handle: CGameHandle, c_ei_ptr: *mut CErrorInfo) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        if lock_registry().games.remove(&handle).is_none() {
+            return_error!(c_ei_ptr, CError::InvalidHandle, INVALID_HANDLE_ERROR);
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_open(
+    handle: CGameHandle,
+    row: GameSizeType,
+    column: GameSizeType,
+    c_open_info_ptr: *mut COpenInfo,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_open(game_ptr, row, column, c_open_info_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_open_neighbors(
+    handle: CGameHandle,
+    row: GameSizeType,
+    column: GameSizeType,
+    c_open_info_ptr: *mut COpenInfo,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_open_neighbors(game_ptr, row, column, c_open_info_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_toggle_flag(
+    handle: CGameHandle,
+    row: GameSizeType,
+    column: GameSizeType,
+    field_flag_result_ptr: *mut FlagResult,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_toggle_flag(game_ptr, row, column, field_flag_result_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_pause(handle: CGameHandle, c_ei_ptr: *mut CErrorInfo) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_pause(game_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_resume(handle: CGameHandle, c_ei_ptr: *mut CErrorInfo) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_resume(game_ptr, c_ei_ptr)
+    });
+}
+
+/// The callbacks are called while the game is locked, so they must not use the same handle.
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_set_callbacks(
+    handle: CGameHandle,
+    callbacks_ptr: *const CCallbacks,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_set_callbacks(game_ptr, callbacks_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_update_timer(
+    handle: CGameHandle,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_update_timer(game_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_width(
+    handle: CGameHandle,
+    width_ptr: *mut GameSizeType,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_width(game_ptr, width_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_height(
+    handle: CGameHandle,
+    height_ptr: *mut GameSizeType,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_height(game_ptr, height_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_elapsed_seconds(
+    handle: CGameHandle,
+    elapsed_seconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_elapsed_seconds(game_ptr, elapsed_seconds_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_elapsed_milliseconds(
+    handle: CGameHandle,
+    elapsed_milliseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_elapsed_milliseconds(game_ptr, elapsed_milliseconds_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_elapsed_nanoseconds(
+    handle: CGameHandle,
+    elapsed_nanoseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_elapsed_nanoseconds(game_ptr, elapsed_nanoseconds_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_started_at_nanoseconds(
+    handle: CGameHandle,
+    started_at_nanoseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_started_at_nanoseconds(game_ptr, started_at_nanoseconds_ptr, c_ei_ptr)
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_handle_game_get_field_info(
+    handle: CGameHandle,
+    row: GameSizeType,
+    column: GameSizeType,
+    field_info_ptr: *mut FieldInfo,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    with_game(handle, c_ei_ptr, |game_ptr| {
+        minesweeper_game_get_field_info(game_ptr, row, column, field_info_ptr, c_ei_ptr)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+
+    fn create_empty_error_info() -> CErrorInfo {
+        CErrorInfo {
+            error_code: CError::UnexpectedError,
+            error_message_length: 0,
+            error_message_max_length: 0,
+            error_message: std::ptr::null_mut(),
+        }
+    }
+
+    fn create_handle() -> CGameHandle {
+        let mut error_info = create_empty_error_info();
+        let mut handle = 0;
+        minesweeper_handle_new_game(GameLevel::Beginner, &mut handle, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert_ne!(0, handle);
+        handle
+    }
+
+    fn destroy_handle(handle: CGameHandle) {
+        let mut error_info = create_empty_error_info();
+        minesweeper_handle_destroy_game(handle, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+    }
+
+    #[test]
+    fn create_and_use_handle() {
+        let handle = create_handle();
+        let mut error_info = create_empty_error_info();
+        let mut width = 0;
+        minesweeper_handle_game_get_width(handle, &mut width, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert_eq!(10, width);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_handle_game_toggle_flag(handle, 0, 0, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert_eq!(FlagResult::Flagged, flag_result);
+        destroy_handle(handle);
+    }
+
+    #[test]
+    fn handles_are_unique() {
+        let first_handle = create_handle();
+        let second_handle = create_handle();
+        assert_ne!(first_handle, second_handle);
+        destroy_handle(first_handle);
+        let third_handle = create_handle();
+        assert_ne!(first_handle, third_handle);
+        destroy_handle(second_handle);
+        destroy_handle(third_handle);
+    }
+
+    #[test]
+    fn new_custom_game_with_invalid_parameters() {
+        let mut error_info = create_empty_error_info();
+        let mut handle = 0;
+        minesweeper_handle_new_custom_game(5, 5, 25, &mut handle, &mut error_info);
+        assert_eq!(CError::TooMuchMines, error_info.error_code);
+        assert_eq!(0, handle);
+
+        minesweeper_handle_new_custom_game(5, 5, 5, std::ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+    }
+
+    #[test]
+    fn use_after_destroy() {
+        let handle = create_handle();
+        destroy_handle(handle);
+        let mut error_info = create_empty_error_info();
+        let mut height = 0;
+        minesweeper_handle_game_get_height(handle, &mut height, &mut error_info);
+        assert_eq!(CError::InvalidHandle, error_info.error_code);
+        assert_eq!(0, height);
+
+        error_info = create_empty_error_info();
+        minesweeper_handle_game_pause(handle, &mut error_info);
+        assert_eq!(CError::InvalidHandle, error_info.error_code);
+    }
+
+    #[test]
+    fn double_destroy() {
+        let handle = create_handle();
+        destroy_handle(handle);
+        let mut error_info = create_empty_error_info();
+        minesweeper_handle_destroy_game(handle, &mut error_info);
+        assert_eq!(CError::InvalidHandle, error_info.error_code);
+    }
+
+    #[test]
+    fn invalid_handle() {
+        let mut error_info = create_empty_error_info();
+        let mut elapsed = 0;
+        minesweeper_handle_game_get_elapsed_seconds(0, &mut elapsed, &mut error_info);
+        assert_eq!(CError::InvalidHandle, error_info.error_code);
+    }
+
+    extern "C" fn count_flags(
+        user_data: *mut libc::c_void,
+        _row: GameSizeType,
+        _column: GameSizeType,
+        _flag_result: FlagResult,
+    ) {
+        unsafe { *(user_data as *mut usize) += 1 };
+    }
+
+    #[test]
+    fn set_callbacks() {
+        let handle = create_handle();
+        let mut number_of_flags = 0usize;
+        let callbacks = CCallbacks {
+            user_data: &mut number_of_flags as *mut usize as *mut libc::c_void,
+            on_cell_opened: None,
+            on_cell_flagged: Some(count_flags),
+            on_game_won: None,
+            on_game_lost: None,
+            on_timer_tick: None,
+        };
+        let mut error_info = create_empty_error_info();
+        minesweeper_handle_game_set_callbacks(handle, &callbacks, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_handle_game_toggle_flag(handle, 1, 1, &mut flag_result, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        minesweeper_handle_game_update_timer(handle, &mut error_info);
+        assert_eq!(CError::Ok, error_info.error_code);
+        assert_eq!(1, number_of_flags);
+        destroy_handle(handle);
+
+        minesweeper_handle_game_update_timer(handle, &mut error_info);
+        assert_eq!(CError::InvalidHandle, error_info.error_code);
+    }
+
+    #[test]
+    fn concurrent_usage() {
+        let handle = create_handle();
+        let timer_thread = thread::spawn(move || {
+            let mut error_info = create_empty_error_info();
+            for _ in 0..1000 {
+                let mut elapsed = 0;
+                minesweeper_handle_game_get_elapsed_milliseconds(
+                    handle,
+                    &mut elapsed,
+                    &mut error_info,
+                );
+                assert_eq!(CError::Ok, error_info.error_code);
+            }
+        });
+
+        let mut error_info = create_empty_error_info();
+        for row in 0..10 {
+            for column in 0..10 {
+                let mut flag_result = FlagResult::AlreadyOpened;
+                minesweeper_handle_game_toggle_flag(
+                    handle,
+                    row,
+                    column,
+                    &mut flag_result,
+                    &mut error_info,
+                );
+                assert_eq!(CError::Ok, error_info.error_code);
+                assert_eq!(FlagResult::Flagged, flag_result);
+            }
+        }
+        timer_thread.join().unwrap();
+        destroy_handle(handle);
+    }
+}
@@ -1,9 +1,19 @@
+// The exported functions receive raw pointers from C, they are checked before dereferencing
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 use libc::c_char;
-use minesweeper::{FieldInfo, FieldType, FlagResult, Game, GameLevel, OpenResult};
+use minesweeper::{
+    FieldInfo, FieldType, FlagResult, Game, GameLevel, OpenResult, GAME_IS_NOT_PAUSED_ERROR,
+    GAME_IS_NOT_RUNNING_ERROR, GAME_IS_PAUSED_ERROR, TOO_FEW_MINES_ERROR, TOO_MUCH_FIELDS,
+    TOO_MUCH_MINES_ERROR,
+};
+use std::cell::RefCell;
 use std::cmp;
 use std::convert::TryFrom;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
 use std::slice;
+use std::time::Duration;
 use strum_macros::Display;
 
 #[repr(C)]
@@ -15,43 +25,79 @@ pub enum CError {
     IndexIsOutOfRange,
     InsufficientBuffer,
     UnexpectedError,
+    TooMuchMines,
+    TooFewMines,
+    TooMuchFields,
+    InvalidHandle,
+    GamePaused,
+    GameNotRunning,
+    GameNotPaused,
 }
 
+static GAME_IS_NOT_STARTED_ERROR: &str = "Game is not started!";
+
 type GameSizeType = minesweeper::SizeType;
 type ArraySizeType = u64;
 
-fn panic_if_null(c_ei_ptr: *const CErrorInfo) {
-    if c_ei_ptr.is_null() {
-        panic!("Error info ptr is null!")
-    }
+static UNEXPECTED_PANIC_ERROR: &str = "Unexpected panic!";
+
+thread_local! {
+    static LAST_ERROR: RefCell<(CError, String)> = const { RefCell::new((CError::Ok, String::new())) };
 }
 
-macro_rules! populate_error {
-    ($error_info_ptr:ident, $error_code:expr, $error_msg:expr) => {{
-        panic_if_null($error_info_ptr);
-        let error_info = unsafe { &mut *$error_info_ptr };
-        if $error_msg.len() > 0 && error_info.error_message_max_length > 0 {
-            let ref error_message = $error_msg;
-            let error_msg_len = error_message.len();
-            let src = error_message.as_bytes().as_ptr();
-            let len_without_terminator = cmp::min(
-                usize::try_from(error_info.error_message_max_length - 1).unwrap_or(usize::MAX),
-                error_msg_len,
+fn write_error_info(error_info: &mut CErrorInfo, error_code: CError, error_msg: &str) {
+    if !error_msg.is_empty() && error_info.error_message_max_length > 0 {
+        let error_msg_len = error_msg.len();
+        let src = error_msg.as_bytes().as_ptr();
+        let len_without_terminator = cmp::min(
+            usize::try_from(error_info.error_message_max_length - 1).unwrap_or(usize::MAX),
+            error_msg_len,
+        );
+        unsafe {
+            ptr::copy_nonoverlapping(
+                src,
+                error_info.error_message as *mut u8,
+                len_without_terminator,
             );
-            unsafe {
-                ptr::copy_nonoverlapping(
-                    src,
-                    error_info.error_message as *mut u8,
-                    len_without_terminator,
-                );
-                *error_info
-                    .error_message
-                    .offset(len_without_terminator as isize) = 0;
-                error_info.error_message_length = len_without_terminator as ArraySizeType;
-            }
+            *error_info.error_message.add(len_without_terminator) = 0;
         }
-        error_info.error_code = $error_code;
-    }};
+        error_info.error_message_length = len_without_terminator as ArraySizeType;
+    }
+    error_info.error_code = error_code;
+}
+
+// The error is always stored as the last error of the thread, so it can be queried even if the
+// error info ptr is null
+fn populate_error_info(c_ei_ptr: *mut CErrorInfo, error_code: CError, error_msg: &str) {
+    LAST_ERROR.with(|last_error| {
+        let mut last_error = last_error.borrow_mut();
+        last_error.0 = error_code;
+        last_error.1.clear();
+        last_error.1.push_str(error_msg);
+    });
+    if let Some(error_info) = unsafe { c_ei_ptr.as_mut() } {
+        write_error_info(error_info, error_code, error_msg);
+    }
+}
+
+// Panics must not cross the FFI boundary, so every exported function executes its body with this
+fn catch_panic(c_ei_ptr: *mut CErrorInfo, func: impl FnOnce()) {
+    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(func)) {
+        let error_msg = if let Some(msg) = payload.downcast_ref::<&str>() {
+            msg
+        } else if let Some(msg) = payload.downcast_ref::<String>() {
+            msg.as_str()
+        } else {
+            UNEXPECTED_PANIC_ERROR
+        };
+        populate_error_info(c_ei_ptr, CError::UnexpectedError, error_msg);
+    }
+}
+
+macro_rules! populate_error {
+    ($error_info_ptr:ident, $error_code:expr, $error_msg:expr) => {
+        populate_error_info($error_info_ptr, $error_code, &$error_msg)
+    };
 }
 
 macro_rules! return_error {
@@ -67,6 +113,19 @@ macro_rules! return_error {
     };
 }
 
+// The errors of the game state have their own codes, the others are unexpected
+fn convert_game_error(error_msg: &str) -> CError {
+    if error_msg == GAME_IS_PAUSED_ERROR {
+        CError::GamePaused
+    } else if error_msg == GAME_IS_NOT_RUNNING_ERROR {
+        CError::GameNotRunning
+    } else if error_msg == GAME_IS_NOT_PAUSED_ERROR {
+        CError::GameNotPaused
+    } else {
+        CError::UnexpectedError
+    }
+}
+
 macro_rules! return_or_assign {
     ($x:expr, $error_info_ptr:ident, $error_code:expr) => {
         match $x {
@@ -77,16 +136,21 @@ macro_rules! return_or_assign {
         }
     };
     ($x:expr, $error_info_ptr:ident) => {
-        return_or_assign!($x, $error_info_ptr, CError::UnexpectedError)
+        match $x {
+            Ok(value) => value,
+            Err(error_msg) => {
+                return_error!($error_info_ptr, convert_game_error(error_msg), error_msg);
+            }
+        }
     };
 }
 
 macro_rules! initialize_to_ok {
     ($error_info_ptr:ident) => {
-        panic_if_null($error_info_ptr);
-        let mut error_info = unsafe { &mut *$error_info_ptr };
-        error_info.error_code = CError::Ok;
-        error_info.error_message_length = 0;
+        if let Some(error_info) = unsafe { $error_info_ptr.as_mut() } {
+            error_info.error_message_length = 0;
+        }
+        populate_error!($error_info_ptr, CError::Ok, "");
     };
 }
 
@@ -110,17 +174,41 @@ macro_rules! get_ref_from_ptr {
     };
 }
 
+mod callbacks;
+mod handle;
+
+pub use callbacks::*;
+pub use handle::*;
+
+#[no_mangle]
+pub extern "C" fn minesweeper_get_last_error(c_ei_ptr: *mut CErrorInfo) {
+    catch_panic(c_ei_ptr, || {
+        if let Some(error_info) = unsafe { c_ei_ptr.as_mut() } {
+            error_info.error_message_length = 0;
+            LAST_ERROR.with(|last_error| {
+                let last_error = last_error.borrow();
+                write_error_info(error_info, last_error.0, &last_error.1);
+            });
+        }
+    });
+}
+
+// The last parameter is the maximum number of fields that can be opened, if more fields would be
+// opened then the game is not changed and the required number of fields is returned
+type OpenFunction = fn(
+    &mut Game,
+    GameSizeType,
+    GameSizeType,
+    usize,
+) -> Result<Result<minesweeper::OpenInfo, usize>, &'static str>;
+
 fn open_common(
     game_ptr: *mut Game,
     row: GameSizeType,
     column: GameSizeType,
     c_open_info_ptr: *mut COpenInfo,
     c_ei_ptr: *mut CErrorInfo,
-    open_func: fn(
-        &mut Game,
-        GameSizeType,
-        GameSizeType,
-    ) -> Result<minesweeper::OpenInfo, &'static str>,
+    open_func: OpenFunction,
 ) {
     initialize_to_ok!(c_ei_ptr);
     let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
@@ -129,21 +217,32 @@ fn open_common(
     if c_open_info.newly_opened_fields_length != 0 {
         return_error!(c_ei_ptr, CError::InvalidInput);
     }
-    if c_open_info.newly_opened_fields_max_length == 0 {
-        return_error!(c_ei_ptr, CError::InsufficientBuffer);
-    }
-    if c_open_info.newly_opened_fields_ptr.is_null() {
+    if c_open_info.newly_opened_fields_max_length != 0
+        && c_open_info.newly_opened_fields_ptr.is_null()
+    {
         return_error!(c_ei_ptr, CError::NullPointerAsInput);
     }
 
-    let open_info = return_or_assign!(open_func(game, row, column), c_ei_ptr);
+    let max_number_of_opened_fields =
+        usize::try_from(c_open_info.newly_opened_fields_max_length).unwrap_or(usize::MAX);
+    let open_info = match return_or_assign!(
+        open_func(game, row, column, max_number_of_opened_fields),
+        c_ei_ptr
+    ) {
+        Ok(open_info) => open_info,
+        Err(required_length) => {
+            // The game is not changed, the caller can retry with a big enough buffer
+            c_open_info.newly_opened_fields_length = required_length as ArraySizeType;
+            return_error!(c_ei_ptr, CError::InsufficientBuffer);
+        }
+    };
 
-    if open_info.newly_opened_fields.len() as ArraySizeType
-        > c_open_info.newly_opened_fields_max_length
-    {
+    c_open_info.result = open_info.result;
+    // The buffer might be a null pointer when no field is opened
+    if open_info.newly_opened_fields.is_empty() {
+        c_open_info.newly_opened_fields_length = 0;
         return;
     }
-    c_open_info.result = open_info.result;
     let c_newly_opened_fields: &mut [COpenedField] = unsafe {
         slice::from_raw_parts_mut(
             c_open_info.newly_opened_fields_ptr,
@@ -154,8 +253,8 @@ fn open_common(
     for (coords, field_type) in open_info.newly_opened_fields {
         c_newly_opened_fields[index].row = coords.0;
         c_newly_opened_fields[index].column = coords.1;
-        c_newly_opened_fields[index].field_type = field_type.clone();
-        index = index + 1;
+        c_newly_opened_fields[index].field_type = field_type;
+        index += 1;
     }
     c_open_info.newly_opened_fields_length = index as ArraySizeType;
 }
@@ -164,27 +263,31 @@ fn open_common(
 #[repr(C)]
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct COpenedField {
-    row: GameSizeType,
-    column: GameSizeType,
-    field_type: FieldType,
+    pub row: GameSizeType,
+    pub column: GameSizeType,
+    pub field_type: FieldType,
 }
 
+/// If the buffer is too small for the newly opened fields, InsufficientBuffer is returned, the game
+/// is not changed and newly_opened_fields_length contains the required length. The required length
+/// can be queried by passing zero as newly_opened_fields_max_length. The newly_opened_fields_length
+/// must be zero when an open function is called, so it has to be reset before the struct is reused.
 #[repr(C)]
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct COpenInfo {
-    result: OpenResult,
-    newly_opened_fields_length: ArraySizeType,
-    newly_opened_fields_max_length: ArraySizeType,
-    newly_opened_fields_ptr: *mut COpenedField,
+    pub result: OpenResult,
+    pub newly_opened_fields_length: ArraySizeType,
+    pub newly_opened_fields_max_length: ArraySizeType,
+    pub newly_opened_fields_ptr: *mut COpenedField,
 }
 
 #[repr(C)]
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct CErrorInfo {
-    error_code: CError,
-    error_message_length: ArraySizeType,
-    error_message_max_length: ArraySizeType,
-    error_message: *mut c_char,
+    pub error_code: CError,
+    pub error_message_length: ArraySizeType,
+    pub error_message_max_length: ArraySizeType,
+    pub error_message: *mut c_char,
 }
 
 #[no_mangle]
@@ -193,16 +296,55 @@ pub extern "C" fn minesweeper_new_game(
     game_level: GameLevel,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    if game_ptr_ptr.is_null() {
-        return_error!(c_ei_ptr, CError::NullPointerAsInput);
-    }
-    let game_ptr = get_mut_ref_from_ptr!(game_ptr_ptr, c_ei_ptr);
-    if !game_ptr.is_null() {
-        return_error!(c_ei_ptr, CError::InvalidInput);
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        if game_ptr_ptr.is_null() {
+            return_error!(c_ei_ptr, CError::NullPointerAsInput);
+        }
+        let game_ptr = get_mut_ref_from_ptr!(game_ptr_ptr, c_ei_ptr);
+        if !game_ptr.is_null() {
+            return_error!(c_ei_ptr, CError::InvalidInput);
+        }
+
+        *game_ptr = Box::into_raw(Box::new(Game::new(game_level)));
+    });
+}
+
+fn convert_new_game_error(error_msg: &str) -> CError {
+    if error_msg == TOO_MUCH_MINES_ERROR {
+        CError::TooMuchMines
+    } else if error_msg == TOO_FEW_MINES_ERROR {
+        CError::TooFewMines
+    } else if error_msg == TOO_MUCH_FIELDS {
+        CError::TooMuchFields
+    } else {
+        CError::InvalidInput
     }
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_new_custom_game(
+    game_ptr_ptr: *mut *mut Game,
+    height: GameSizeType,
+    width: GameSizeType,
+    number_of_mines: GameSizeType,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game_ptr = get_mut_ref_from_ptr!(game_ptr_ptr, c_ei_ptr);
+        if !game_ptr.is_null() {
+            return_error!(c_ei_ptr, CError::InvalidInput);
+        }
 
-    *game_ptr = Box::into_raw(Box::new(Game::new(game_level)));
+        let game = match Game::new_custom(height, width, number_of_mines) {
+            Ok(game) => game,
+            Err(error_msg) => {
+                return_error!(c_ei_ptr, convert_new_game_error(error_msg), error_msg);
+            }
+        };
+        *game_ptr = Box::into_raw(Box::new(game));
+    });
 }
 
 #[no_mangle]
@@ -213,14 +355,16 @@ pub extern "C" fn minesweeper_game_open_neighbors(
     c_open_info_ptr: *mut COpenInfo,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    open_common(
-        game_ptr,
-        row,
-        column,
-        c_open_info_ptr,
-        c_ei_ptr,
-        Game::open_neighbors,
-    );
+    catch_panic(c_ei_ptr, || {
+        open_common(
+            game_ptr,
+            row,
+            column,
+            c_open_info_ptr,
+            c_ei_ptr,
+            Game::open_neighbors_with_limit,
+        );
+    });
 }
 
 #[no_mangle]
@@ -231,7 +375,16 @@ pub extern "C" fn minesweeper_game_open(
     c_open_info_ptr: *mut COpenInfo,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    open_common(game_ptr, row, column, c_open_info_ptr, c_ei_ptr, Game::open);
+    catch_panic(c_ei_ptr, || {
+        open_common(
+            game_ptr,
+            row,
+            column,
+            c_open_info_ptr,
+            c_ei_ptr,
+            Game::open_with_limit,
+        );
+    });
 }
 
 #[no_mangle]
@@ -242,10 +395,30 @@ pub extern "C" fn minesweeper_game_toggle_flag(
     field_flag_result_ptr: *mut FlagResult,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
-    let flag_result = get_mut_ref_from_ptr!(field_flag_result_ptr, c_ei_ptr);
-    *flag_result = return_or_assign!(game.toggle_flag(row, column), c_ei_ptr);
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let flag_result = get_mut_ref_from_ptr!(field_flag_result_ptr, c_ei_ptr);
+        *flag_result = return_or_assign!(game.toggle_flag(row, column), c_ei_ptr);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_game_pause(game_ptr: *mut Game, c_ei_ptr: *mut CErrorInfo) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
+        return_or_assign!(game.pause(), c_ei_ptr);
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_game_resume(game_ptr: *mut Game, c_ei_ptr: *mut CErrorInfo) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_mut_ref_from_ptr!(game_ptr, c_ei_ptr);
+        return_or_assign!(game.resume(), c_ei_ptr);
+    });
 }
 
 #[no_mangle]
@@ -253,15 +426,17 @@ pub extern "C" fn minesweeper_destroy_game(
     game_ptr_ptr: *mut *mut Game,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    let game_ptr = get_mut_ref_from_ptr!(game_ptr_ptr, c_ei_ptr);
-    if game_ptr.is_null() {
-        return;
-    }
-    unsafe {
-        drop(Box::<Game>::from_raw(*game_ptr));
-    };
-    *game_ptr = std::ptr::null_mut();
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game_ptr = get_mut_ref_from_ptr!(game_ptr_ptr, c_ei_ptr);
+        if game_ptr.is_null() {
+            return;
+        }
+        unsafe {
+            drop(Box::<Game>::from_raw(*game_ptr));
+        };
+        *game_ptr = std::ptr::null_mut();
+    });
 }
 
 #[no_mangle]
@@ -270,13 +445,15 @@ pub extern "C" fn minesweeper_game_get_width(
     width_ptr: *mut GameSizeType,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    if game_ptr.is_null() || width_ptr.is_null() {
-        return_error!(c_ei_ptr, CError::NullPointerAsInput);
-    }
-    let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
-    let width = get_mut_ref_from_ptr!(width_ptr, c_ei_ptr);
-    *width = game.width();
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        if game_ptr.is_null() || width_ptr.is_null() {
+            return_error!(c_ei_ptr, CError::NullPointerAsInput);
+        }
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let width = get_mut_ref_from_ptr!(width_ptr, c_ei_ptr);
+        *width = game.width();
+    });
 }
 
 #[no_mangle]
@@ -285,13 +462,15 @@ pub extern "C" fn minesweeper_game_get_height(
     height_ptr: *mut GameSizeType,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    if game_ptr.is_null() || height_ptr.is_null() {
-        return_error!(c_ei_ptr, CError::NullPointerAsInput);
-    }
-    let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
-    let height = get_mut_ref_from_ptr!(height_ptr, c_ei_ptr);
-    *height = game.height();
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        if game_ptr.is_null() || height_ptr.is_null() {
+            return_error!(c_ei_ptr, CError::NullPointerAsInput);
+        }
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let height = get_mut_ref_from_ptr!(height_ptr, c_ei_ptr);
+        *height = game.height();
+    });
 }
 
 #[no_mangle]
@@ -300,11 +479,66 @@ pub extern "C" fn minesweeper_game_get_elapsed_seconds(
     elapsed_seconds_ptr: *mut u64,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
-    let elapsed_seconds = get_mut_ref_from_ptr!(elapsed_seconds_ptr, c_ei_ptr);
-    let elapsed_duration = game.get_elapsed();
-    *elapsed_seconds = elapsed_duration.as_secs();
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let elapsed_seconds = get_mut_ref_from_ptr!(elapsed_seconds_ptr, c_ei_ptr);
+        let elapsed_duration = game.get_elapsed();
+        *elapsed_seconds = elapsed_duration.as_secs();
+    });
+}
+
+fn duration_to_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_game_get_elapsed_milliseconds(
+    game_ptr: *const Game,
+    elapsed_milliseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let elapsed_milliseconds = get_mut_ref_from_ptr!(elapsed_milliseconds_ptr, c_ei_ptr);
+        *elapsed_milliseconds = duration_to_nanos(game.get_elapsed()) / 1_000_000;
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn minesweeper_game_get_elapsed_nanoseconds(
+    game_ptr: *const Game,
+    elapsed_nanoseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let elapsed_nanoseconds = get_mut_ref_from_ptr!(elapsed_nanoseconds_ptr, c_ei_ptr);
+        *elapsed_nanoseconds = duration_to_nanos(game.get_elapsed());
+    });
+}
+
+// The monotonic timestamp of the first move in nanoseconds, the origin of the timestamps is
+// unspecified, but it is the same for every game
+#[no_mangle]
+pub extern "C" fn minesweeper_game_get_started_at_nanoseconds(
+    game_ptr: *const Game,
+    started_at_nanoseconds_ptr: *mut u64,
+    c_ei_ptr: *mut CErrorInfo,
+) {
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let started_at_nanoseconds = get_mut_ref_from_ptr!(started_at_nanoseconds_ptr, c_ei_ptr);
+        let started_at = return_or_assign!(
+            game.get_started_at().ok_or(GAME_IS_NOT_STARTED_ERROR),
+            c_ei_ptr,
+            CError::InvalidInput
+        );
+        *started_at_nanoseconds = duration_to_nanos(started_at);
+    });
 }
 
 #[no_mangle]
@@ -315,17 +549,19 @@ pub extern "C" fn minesweeper_game_get_field_info(
     field_info_ptr: *mut FieldInfo,
     c_ei_ptr: *mut CErrorInfo,
 ) {
-    initialize_to_ok!(c_ei_ptr);
-    let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
-    let field_info = get_mut_ref_from_ptr!(field_info_ptr, c_ei_ptr);
+    catch_panic(c_ei_ptr, || {
+        initialize_to_ok!(c_ei_ptr);
+        let game = get_ref_from_ptr!(game_ptr, c_ei_ptr);
+        let field_info = get_mut_ref_from_ptr!(field_info_ptr, c_ei_ptr);
 
-    *field_info = return_or_assign!(game.get_field_info(row, column), c_ei_ptr);
+        *field_info = return_or_assign!(game.get_field_info(row, column), c_ei_ptr);
+    });
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use minesweeper::FieldState;
+    use minesweeper::{Clock, FieldState, ManualClock};
     use std::ffi::CStr;
 
     macro_rules! assert_ok {
@@ -359,8 +595,8 @@ mod test {
         buffer.resize(
             size,
             COpenedField {
-                row: GameSizeType::max_value(),
-                column: GameSizeType::max_value(),
+                row: GameSizeType::MAX,
+                column: GameSizeType::MAX,
                 field_type: FieldType::Mine,
             },
         );
@@ -419,6 +655,12 @@ mod test {
         result
     }
 
+    fn get_last_error_code() -> CError {
+        let mut error_info = create_empty_error_info();
+        minesweeper_get_last_error(&mut error_info);
+        error_info.error_code
+    }
+
     fn get_width(game_ptr: *mut Game) -> GameSizeType {
         let mut error_info = create_empty_error_info();
         let mut width = 0;
@@ -454,7 +696,7 @@ mod test {
         check_no_error(&error_info);
         assert_eq!(elapsed_seconds, 0);
 
-        return game_ptr;
+        game_ptr
     }
 
     fn destroy_game(game_ptr: &mut *mut Game) {
@@ -463,6 +705,87 @@ mod test {
         check_no_error(&error_info);
     }
 
+    #[test]
+    fn committed_header_is_up_to_date() {
+        let committed_header = include_str!("../include/cminesweeper.h").replace("\r\n", "\n");
+        let generated_header = include_str!(concat!(env!("OUT_DIR"), "/cminesweeper.h"));
+        assert!(
+            committed_header == generated_header,
+            "The committed header is out of date, please update it from {}/cminesweeper.h!",
+            env!("OUT_DIR")
+        );
+    }
+
+    #[derive(Debug)]
+    struct PanickingClock;
+
+    impl Clock for PanickingClock {
+        fn now(&self) -> Duration {
+            panic!("The clock is broken!")
+        }
+    }
+
+    fn get_error_message(error_info: &CErrorInfo) -> String {
+        let error_msg = unsafe { CStr::from_ptr(error_info.error_message) };
+        error_msg.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn panics_do_not_escape() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        unsafe { &mut *game_ptr }
+            .set_clock(Box::new(PanickingClock))
+            .unwrap();
+        let mut buffered_error_info = create_error_info(100);
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(
+            game_ptr,
+            0,
+            0,
+            &mut flag_result,
+            &mut buffered_error_info.data,
+        );
+        assert_eq!(CError::UnexpectedError, buffered_error_info.data.error_code);
+        assert_eq!(
+            "The clock is broken!",
+            get_error_message(&buffered_error_info.data)
+        );
+
+        let mut buffered_open_info = create_open_info_for(game_ptr);
+        minesweeper_game_open(
+            game_ptr,
+            0,
+            0,
+            &mut buffered_open_info.data,
+            std::ptr::null_mut(),
+        );
+        assert_eq!(CError::UnexpectedError, get_last_error_code());
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn last_error() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut buffered_error_info = create_error_info(100);
+        let width = get_width(game_ptr);
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 0, width, &mut flag_result, std::ptr::null_mut());
+
+        minesweeper_get_last_error(&mut buffered_error_info.data);
+        assert_eq!(CError::UnexpectedError, buffered_error_info.data.error_code);
+        assert_eq!(
+            "Invalid index!",
+            get_error_message(&buffered_error_info.data)
+        );
+        // Querying the last error doesn't change it
+        assert_eq!(CError::UnexpectedError, get_last_error_code());
+        minesweeper_get_last_error(std::ptr::null_mut());
+
+        get_width(game_ptr);
+        assert_eq!(CError::Ok, get_last_error_code());
+        destroy_game(&mut game_ptr);
+    }
+
     #[test]
     fn new_game_with_nullptr() {
         let mut error_info = create_empty_error_info();
@@ -481,11 +804,66 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn new_game_with_nullptr_as_error_info_ptr() {
         let level = GameLevel::Beginner;
         let mut game_ptr: *mut Game = std::ptr::null_mut();
         minesweeper_new_game(&mut game_ptr, level, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert!(!game_ptr.is_null());
+
+        minesweeper_new_game(&mut game_ptr, level, std::ptr::null_mut());
+        assert_eq!(CError::InvalidInput, get_last_error_code());
+        destroy_game(&mut game_ptr);
+    }
+
+    fn check_new_custom_game_error(
+        height: GameSizeType,
+        width: GameSizeType,
+        number_of_mines: GameSizeType,
+        expected_error: CError,
+    ) {
+        let mut buffered_error_info = create_error_info(100);
+        let mut game_ptr: *mut Game = std::ptr::null_mut();
+        minesweeper_new_custom_game(
+            &mut game_ptr,
+            height,
+            width,
+            number_of_mines,
+            &mut buffered_error_info.data,
+        );
+        assert_eq!(expected_error, buffered_error_info.data.error_code);
+        assert!(buffered_error_info.data.error_message_length > 0);
+        assert!(game_ptr.is_null());
+    }
+
+    #[test]
+    fn new_custom_game() {
+        let mut error_info = create_empty_error_info();
+        let mut game_ptr: *mut Game = std::ptr::null_mut();
+        minesweeper_new_custom_game(&mut game_ptr, 5, 8, 10, &mut error_info);
+        check_no_error(&error_info);
+        assert!(!game_ptr.is_null());
+        assert_eq!(5, get_height(game_ptr));
+        assert_eq!(8, get_width(game_ptr));
+
+        minesweeper_new_custom_game(&mut game_ptr, 5, 8, 10, &mut error_info);
+        assert_eq!(CError::InvalidInput, error_info.error_code);
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn new_custom_game_with_nullptr() {
+        let mut error_info = create_empty_error_info();
+        minesweeper_new_custom_game(std::ptr::null_mut(), 5, 5, 5, &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+    }
+
+    #[test]
+    fn new_custom_game_with_invalid_parameters() {
+        check_new_custom_game_error(5, 5, 25, CError::TooMuchMines);
+        check_new_custom_game_error(5, 5, 0, CError::TooFewMines);
+        check_new_custom_game_error(GameSizeType::MAX, 2, 1, CError::TooMuchFields);
+        check_new_custom_game_error(0, 5, 1, CError::InvalidInput);
     }
 
     #[test]
@@ -505,10 +883,14 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn destroy_game_with_nullptr_as_error_info_ptr() {
         let mut game_ptr = create_game(GameLevel::Beginner);
         minesweeper_destroy_game(&mut game_ptr, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert!(game_ptr.is_null());
+
+        minesweeper_destroy_game(std::ptr::null_mut(), std::ptr::null_mut());
+        assert_eq!(CError::NullPointerAsInput, get_last_error_code());
     }
 
     #[test]
@@ -591,10 +973,9 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn open_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
-        let mut buffered_open_info = create_open_info_with_size(5);
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut buffered_open_info = create_open_info_for(game_ptr);
         minesweeper_game_open(
             game_ptr,
             0,
@@ -602,6 +983,12 @@ mod test {
             &mut buffered_open_info.data,
             std::ptr::null_mut(),
         );
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert!(buffered_open_info.data.newly_opened_fields_length > 0);
+
+        minesweeper_game_open(game_ptr, 0, 0, std::ptr::null_mut(), std::ptr::null_mut());
+        assert_eq!(CError::NullPointerAsInput, get_last_error_code());
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -655,6 +1042,88 @@ mod test {
         destroy_game(&mut game_ptr);
     }
 
+    #[test]
+    fn open_with_two_phase_buffer_sizing() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut error_info = create_empty_error_info();
+        let mut buffered_open_info = create_open_info_with_size(0);
+        buffered_open_info.data.newly_opened_fields_ptr = std::ptr::null_mut();
+        minesweeper_game_open(
+            game_ptr,
+            1,
+            1,
+            &mut buffered_open_info.data,
+            &mut error_info,
+        );
+        assert_eq!(CError::InsufficientBuffer, error_info.error_code);
+        let required_length = buffered_open_info.data.newly_opened_fields_length;
+        assert!(required_length > 0);
+
+        let mut field_info = FieldInfo {
+            state: FieldState::Opened,
+            field_type: FieldType::Mine,
+        };
+        error_info = create_empty_error_info();
+        minesweeper_game_get_field_info(game_ptr, 1, 1, &mut field_info, &mut error_info);
+        check_no_error(&error_info);
+        assert_eq!(FieldState::Closed, field_info.state);
+
+        // The mines are moved the same way, so the required length is exactly enough
+        let mut buffer = create_open_info_with_size(required_length as usize).buffer;
+        let mut open_info = buffered_open_info.data;
+        open_info.newly_opened_fields_ptr = buffer.as_mut_ptr();
+        open_info.newly_opened_fields_max_length = required_length;
+
+        // The length must be reset before the struct is reused
+        error_info = create_empty_error_info();
+        minesweeper_game_open(game_ptr, 1, 1, &mut open_info, &mut error_info);
+        assert_eq!(CError::InvalidInput, error_info.error_code);
+
+        open_info.newly_opened_fields_length = 0;
+        error_info = create_empty_error_info();
+        minesweeper_game_open(game_ptr, 1, 1, &mut open_info, &mut error_info);
+        check_no_error(&error_info);
+        assert_eq!(required_length, open_info.newly_opened_fields_length);
+        assert!(buffer.iter().all(|field| field.row != GameSizeType::MAX));
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn open_flagged_field_with_null_buffer() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut error_info = create_empty_error_info();
+        let mut flag_result = FlagResult::AlreadyOpened;
+        minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, &mut error_info);
+        check_no_error(&error_info);
+        assert_eq!(FlagResult::Flagged, flag_result);
+
+        let mut buffered_open_info = create_open_info_with_size(0);
+        buffered_open_info.data.newly_opened_fields_ptr = std::ptr::null_mut();
+        error_info = create_empty_error_info();
+        minesweeper_game_open(
+            game_ptr,
+            0,
+            0,
+            &mut buffered_open_info.data,
+            &mut error_info,
+        );
+        check_no_error(&error_info);
+        assert_eq!(OpenResult::IsFlagged, buffered_open_info.data.result);
+        assert_eq!(0, buffered_open_info.data.newly_opened_fields_length);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_open_neighbors(
+            game_ptr,
+            0,
+            0,
+            &mut buffered_open_info.data,
+            &mut error_info,
+        );
+        check_no_error(&error_info);
+        assert_eq!(0, buffered_open_info.data.newly_opened_fields_length);
+        destroy_game(&mut game_ptr);
+    }
+
     #[test]
     fn open_with_too_big_indices() {
         let mut game_ptr = create_game(GameLevel::Beginner);
@@ -736,11 +1205,13 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn toggle_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
+        let mut game_ptr = create_game(GameLevel::Beginner);
         let mut flag_result = FlagResult::AlreadyOpened;
         minesweeper_game_toggle_flag(game_ptr, 0, 0, &mut flag_result, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert_eq!(FlagResult::Flagged, flag_result);
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -787,6 +1258,97 @@ mod test {
         destroy_game(&mut game_ptr);
     }
 
+    #[test]
+    fn pause_and_resume() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut buffered_error_info = create_error_info(100);
+        minesweeper_game_pause(game_ptr, &mut buffered_error_info.data);
+        assert_eq!(CError::GameNotRunning, buffered_error_info.data.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_toggle_flag(
+            game_ptr,
+            0,
+            0,
+            &mut flag_result,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_pause(game_ptr, &mut buffered_error_info.data);
+        assert_ok!(buffered_error_info.data);
+
+        let mut buffered_open_info = create_open_info_for(game_ptr);
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_open(
+            game_ptr,
+            1,
+            1,
+            &mut buffered_open_info.data,
+            &mut buffered_error_info.data,
+        );
+        assert_eq!(CError::GamePaused, buffered_error_info.data.error_code);
+        assert_eq!(0, buffered_open_info.data.newly_opened_fields_length);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_toggle_flag(
+            game_ptr,
+            0,
+            0,
+            &mut flag_result,
+            &mut buffered_error_info.data,
+        );
+        assert_eq!(CError::GamePaused, buffered_error_info.data.error_code);
+
+        let mut field_info = FieldInfo {
+            state: FieldState::Opened,
+            field_type: FieldType::Mine,
+        };
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_get_field_info(
+            game_ptr,
+            0,
+            0,
+            &mut field_info,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(FieldState::Closed, field_info.state);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_resume(game_ptr, &mut buffered_error_info.data);
+        assert_ok!(buffered_error_info.data);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_resume(game_ptr, &mut buffered_error_info.data);
+        assert_eq!(CError::GameNotPaused, buffered_error_info.data.error_code);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_toggle_flag(
+            game_ptr,
+            0,
+            0,
+            &mut flag_result,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(FlagResult::FlagRemoved, flag_result);
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn pause_and_resume_with_nullptr_as_game() {
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_pause(std::ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_resume(std::ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+    }
+
     #[test]
     fn get_width_with_nullptr_as_game() {
         let mut error_info = create_empty_error_info();
@@ -805,11 +1367,16 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn get_width_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
+        let mut game_ptr = create_game(GameLevel::Beginner);
         let mut width = 0;
         minesweeper_game_get_width(game_ptr, &mut width, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert_eq!(10, width);
+
+        minesweeper_game_get_width(game_ptr, std::ptr::null_mut(), std::ptr::null_mut());
+        assert_eq!(CError::NullPointerAsInput, get_last_error_code());
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -830,11 +1397,13 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn get_height_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
+        let mut game_ptr = create_game(GameLevel::Beginner);
         let mut height = 0;
         minesweeper_game_get_height(game_ptr, &mut height, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert_eq!(10, height);
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -855,11 +1424,122 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn get_elapsed_seconds_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
-        let mut elapsed_seconds = 0;
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut elapsed_seconds = 1;
         minesweeper_game_get_elapsed_seconds(game_ptr, &mut elapsed_seconds, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert_eq!(0, elapsed_seconds);
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn get_elapsed_with_sub_second_precision() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let clock = ManualClock::new();
+        clock.advance(Duration::from_secs(10));
+        unsafe { &mut *game_ptr }
+            .set_clock(Box::new(clock.clone()))
+            .unwrap();
+        let mut buffered_error_info = create_error_info(100);
+        let mut started_at = 0;
+        minesweeper_game_get_started_at_nanoseconds(
+            game_ptr,
+            &mut started_at,
+            &mut buffered_error_info.data,
+        );
+        assert_eq!(CError::InvalidInput, buffered_error_info.data.error_code);
+
+        let mut flag_result = FlagResult::AlreadyOpened;
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_toggle_flag(
+            game_ptr,
+            0,
+            0,
+            &mut flag_result,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        clock.advance(Duration::from_nanos(1_234_567_890));
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_get_started_at_nanoseconds(
+            game_ptr,
+            &mut started_at,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(10_000_000_000, started_at);
+
+        let mut elapsed = 0;
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_get_elapsed_nanoseconds(
+            game_ptr,
+            &mut elapsed,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(1_234_567_890, elapsed);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_get_elapsed_milliseconds(
+            game_ptr,
+            &mut elapsed,
+            &mut buffered_error_info.data,
+        );
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(1_234, elapsed);
+
+        reset_error_info(&mut buffered_error_info.data);
+        minesweeper_game_get_elapsed_seconds(game_ptr, &mut elapsed, &mut buffered_error_info.data);
+        assert_ok!(buffered_error_info.data);
+        assert_eq!(1, elapsed);
+        destroy_game(&mut game_ptr);
+    }
+
+    #[test]
+    fn get_sub_second_timing_with_nullptr_as_game() {
+        let mut error_info = create_empty_error_info();
+        let mut value = 0;
+        minesweeper_game_get_elapsed_milliseconds(
+            std::ptr::null_mut(),
+            &mut value,
+            &mut error_info,
+        );
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_get_elapsed_nanoseconds(std::ptr::null_mut(), &mut value, &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_get_started_at_nanoseconds(
+            std::ptr::null_mut(),
+            &mut value,
+            &mut error_info,
+        );
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+    }
+
+    #[test]
+    fn get_sub_second_timing_with_nullptr_as_output() {
+        let mut game_ptr = create_game(GameLevel::Beginner);
+        let mut error_info = create_empty_error_info();
+        minesweeper_game_get_elapsed_milliseconds(game_ptr, std::ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_get_elapsed_nanoseconds(game_ptr, std::ptr::null_mut(), &mut error_info);
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+
+        error_info = create_empty_error_info();
+        minesweeper_game_get_started_at_nanoseconds(
+            game_ptr,
+            std::ptr::null_mut(),
+            &mut error_info,
+        );
+        assert_eq!(CError::NullPointerAsInput, error_info.error_code);
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -906,9 +1586,8 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn open_neighbors_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
+        let mut game_ptr = create_game(GameLevel::Beginner);
         let mut buffered_open_info = create_open_info_with_size(5);
         minesweeper_game_open_neighbors(
             game_ptr,
@@ -917,6 +1596,8 @@ mod test {
             &mut buffered_open_info.data,
             std::ptr::null_mut(),
         );
+        assert_eq!(CError::Ok, get_last_error_code());
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -966,7 +1647,9 @@ mod test {
             &mut buffered_open_info.data,
             &mut error_info,
         );
-        assert_eq!(CError::InsufficientBuffer, error_info.error_code);
+        // The neighbors of a closed field are not opened, so no buffer is needed
+        check_no_error(&error_info);
+        assert_eq!(0, buffered_open_info.data.newly_opened_fields_length);
         destroy_game(&mut game_ptr);
     }
 
@@ -1031,7 +1714,7 @@ mod test {
 
         for newly_opened_field in buffered_open_info.buffer[0..newly_opened_fields_length].iter() {
             if newly_opened_field.row == row && newly_opened_field.column == col {
-                expected_field_type = newly_opened_field.field_type.clone();
+                expected_field_type = newly_opened_field.field_type;
                 break;
             }
         }
@@ -1068,14 +1751,16 @@ mod test {
     }
 
     #[test]
-    #[should_panic(expected = "Error info ptr is null!")]
     fn get_field_info_with_nullptr_as_error_info_ptr() {
-        let game_ptr = create_game(GameLevel::Beginner);
+        let mut game_ptr = create_game(GameLevel::Beginner);
         let mut field_info = FieldInfo {
-            state: FieldState::Closed,
-            field_type: FieldType::Empty,
+            state: FieldState::Opened,
+            field_type: FieldType::Mine,
         };
         minesweeper_game_get_field_info(game_ptr, 0, 0, &mut field_info, std::ptr::null_mut());
+        assert_eq!(CError::Ok, get_last_error_code());
+        assert_eq!(FieldState::Closed, field_info.state);
+        destroy_game(&mut game_ptr);
     }
 
     #[test]
@@ -0,0 +1,33 @@
+use minesweeper::Clock;
+use std::time::Duration;
+
+// std::time::Instant is not available on wasm32-unknown-unknown, therefore the time is measured by
+// the performance API of the JavaScript environment, which is available in browsers and Node.js
+#[cfg(target_arch = "wasm32")]
+mod performance {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = performance)]
+        pub fn now() -> f64;
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct PerformanceClock;
+
+#[cfg(target_arch = "wasm32")]
+impl Clock for PerformanceClock {
+    fn now(&self) -> Duration {
+        Duration::from_secs_f64(performance::now().max(0.0) / 1000.0)
+    }
+}
+
+// Makes it possible to use the wrapper natively, e.g. in the unit tests
+#[cfg(not(target_arch = "wasm32"))]
+impl Clock for PerformanceClock {
+    fn now(&self) -> Duration {
+        minesweeper::MonotonicClock.now()
+    }
+}
@@ -0,0 +1,271 @@
+// WebAssembly bindings of the minesweeper lib. The errors are thrown as strings, the fields of the
+// board can be queried at once by fieldValues.
+use minesweeper::{FlagResult, Game, GameLevel, GameState, OpenResult, SizeType};
+use wasm_bindgen::prelude::*;
+
+mod clock;
+
+pub use clock::PerformanceClock;
+
+// The values returned by fieldValues
+pub use minesweeper::{CLOSED_VALUE, FLAGGED_VALUE, MINE_VALUE, VOID_VALUE};
+
+#[wasm_bindgen(js_name = GameLevel)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum JsGameLevel {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl From<JsGameLevel> for GameLevel {
+    fn from(level: JsGameLevel) -> GameLevel {
+        match level {
+            JsGameLevel::Beginner => GameLevel::Beginner,
+            JsGameLevel::Intermediate => GameLevel::Intermediate,
+            JsGameLevel::Expert => GameLevel::Expert,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = GameState)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum JsGameState {
+    NotStarted,
+    Started,
+    Paused,
+    Won,
+    Lost,
+}
+
+impl From<GameState> for JsGameState {
+    fn from(state: GameState) -> JsGameState {
+        match state {
+            GameState::NotStarted => JsGameState::NotStarted,
+            GameState::Started => JsGameState::Started,
+            GameState::Paused => JsGameState::Paused,
+            GameState::Won => JsGameState::Won,
+            GameState::Lost(_) => JsGameState::Lost,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = FlagResult)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum JsFlagResult {
+    Flagged,
+    FlagRemoved,
+    AlreadyOpened,
+}
+
+impl From<FlagResult> for JsFlagResult {
+    fn from(flag_result: FlagResult) -> JsFlagResult {
+        match flag_result {
+            FlagResult::Flagged => JsFlagResult::Flagged,
+            FlagResult::FlagRemoved => JsFlagResult::FlagRemoved,
+            FlagResult::AlreadyOpened => JsFlagResult::AlreadyOpened,
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = OpenResult)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum JsOpenResult {
+    Ok,
+    IsFlagged,
+    Boom,
+    Winner,
+    TimeIsUp,
+    OutOfMoves,
+}
+
+impl From<OpenResult> for JsOpenResult {
+    fn from(open_result: OpenResult) -> JsOpenResult {
+        match open_result {
+            OpenResult::Ok => JsOpenResult::Ok,
+            OpenResult::IsFlagged => JsOpenResult::IsFlagged,
+            OpenResult::Boom => JsOpenResult::Boom,
+            OpenResult::WINNER => JsOpenResult::Winner,
+            OpenResult::TimeIsUp => JsOpenResult::TimeIsUp,
+            OpenResult::OutOfMoves => JsOpenResult::OutOfMoves,
+        }
+    }
+}
+
+fn to_js_error(error: &str) -> String {
+    error.to_string()
+}
+
+#[wasm_bindgen(js_name = Game)]
+pub struct JsGame {
+    game: Game,
+}
+
+impl JsGame {
+    fn from_game(mut game: Game) -> JsGame {
+        game.set_clock(Box::new(PerformanceClock))
+            .expect("The clock of a new game must be settable!");
+        JsGame { game }
+    }
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl JsGame {
+    #[wasm_bindgen(constructor)]
+    pub fn new(level: JsGameLevel) -> JsGame {
+        JsGame::from_game(Game::new(level.into()))
+    }
+
+    #[wasm_bindgen(js_name = withSeed)]
+    pub fn with_seed(level: JsGameLevel, seed: u32) -> JsGame {
+        JsGame::from_game(Game::new_with_seed(level.into(), seed as u64))
+    }
+
+    pub fn custom(
+        height: u32,
+        width: u32,
+        number_of_mines: u32,
+        seed: Option<u32>,
+    ) -> Result<JsGame, String> {
+        let (height, width, number_of_mines) = (
+            height as SizeType,
+            width as SizeType,
+            number_of_mines as SizeType,
+        );
+        let game = match seed {
+            Some(seed) => Game::new_custom_with_seed(height, width, number_of_mines, seed as u64),
+            None => Game::new_custom(height, width, number_of_mines),
+        };
+        game.map(JsGame::from_game).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.game.width() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.game.height() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> JsGameState {
+        self.game.get_state().into()
+    }
+
+    #[wasm_bindgen(js_name = elapsedMilliseconds)]
+    pub fn elapsed_milliseconds(&self) -> f64 {
+        self.game.get_elapsed().as_secs_f64() * 1000.0
+    }
+
+    // Checks the time limit, it should be called periodically by the timer of the front-end
+    #[wasm_bindgen(js_name = updateTimer)]
+    pub fn update_timer(&mut self) {
+        self.game.update_timer();
+    }
+
+    pub fn open(&mut self, row: u32, col: u32) -> Result<JsOpenResult, String> {
+        let open_info = self
+            .game
+            .open(row as SizeType, col as SizeType)
+            .map_err(to_js_error)?;
+        Ok(open_info.result.into())
+    }
+
+    #[wasm_bindgen(js_name = openNeighbors)]
+    pub fn open_neighbors(&mut self, row: u32, col: u32) -> Result<JsOpenResult, String> {
+        let open_info = self
+            .game
+            .open_neighbors(row as SizeType, col as SizeType)
+            .map_err(to_js_error)?;
+        Ok(open_info.result.into())
+    }
+
+    #[wasm_bindgen(js_name = toggleFlag)]
+    pub fn toggle_flag(&mut self, row: u32, col: u32) -> Result<JsFlagResult, String> {
+        let flag_result = self
+            .game
+            .toggle_flag(row as SizeType, col as SizeType)
+            .map_err(to_js_error)?;
+        Ok(flag_result.into())
+    }
+
+    pub fn pause(&mut self) -> Result<(), String> {
+        self.game.pause().map_err(to_js_error)
+    }
+
+    pub fn resume(&mut self) -> Result<(), String> {
+        self.game.resume().map_err(to_js_error)
+    }
+
+    // Returns the values of the fields row by row, it is converted to an Int8Array
+    #[wasm_bindgen(js_name = fieldValues)]
+    pub fn field_values(&self) -> Result<Vec<i8>, String> {
+        let mut values = Vec::with_capacity((self.game.height() * self.game.width()) as usize);
+        for row in 0..self.game.height() {
+            for col in 0..self.game.width() {
+                let field_info = self.game.get_field_info(row, col).map_err(to_js_error)?;
+                values.push(field_info.to_value());
+            }
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    // The tests are run in a headless browser on wasm32 and as normal tests on the other targets
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn new_game() {
+        let game = JsGame::new(JsGameLevel::Expert);
+        assert_eq!(30, game.width());
+        assert_eq!(16, game.height());
+        assert_eq!(JsGameState::NotStarted, game.state());
+        assert_eq!(0.0, game.elapsed_milliseconds());
+        let values = game.field_values().unwrap();
+        assert_eq!(16 * 30, values.len());
+        assert!(values.iter().all(|value| *value == CLOSED_VALUE));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn same_seed_same_game() {
+        let mut game = JsGame::with_seed(JsGameLevel::Intermediate, 7);
+        let mut other_game = JsGame::custom(16, 16, 25, Some(7)).unwrap();
+        assert_eq!(game.open(8, 8), other_game.open(8, 8));
+        assert_eq!(game.field_values(), other_game.field_values());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn play() {
+        let mut game = JsGame::custom(2, 2, 3, Some(1)).unwrap();
+        assert_eq!(Ok(JsFlagResult::Flagged), game.toggle_flag(0, 0));
+        assert_eq!(JsGameState::Started, game.state());
+        game.pause().unwrap();
+        assert_eq!(JsGameState::Paused, game.state());
+        assert_eq!(
+            Err(minesweeper::GAME_IS_PAUSED_ERROR.to_string()),
+            game.open(1, 1)
+        );
+        assert_eq!(Ok(vec![CLOSED_VALUE; 4]), game.field_values());
+        game.resume().unwrap();
+        game.open(1, 1).unwrap();
+        let values = game.field_values().unwrap();
+        assert_eq!(FLAGGED_VALUE, values[0]);
+        assert_eq!(3, values[3]);
+        assert!(game.open(2, 0).is_err());
+        game.update_timer();
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn invalid_custom_game() {
+        assert!(JsGame::custom(2, 2, 4, None).is_err());
+        assert!(JsGame::custom(0, 2, 1, None).is_err());
+    }
+}
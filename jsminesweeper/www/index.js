@@ -0,0 +1,114 @@
+// The pkg directory is generated by wasm-bindgen, see the README of jsminesweeper
+import init, { Game, GameLevel, GameState } from "./pkg/jsminesweeper.js";
+
+const CLOSED = -1;
+const FLAGGED = -2;
+const MINE = -3;
+const VOID = -4;
+
+const board = document.getElementById("board");
+const timer = document.getElementById("timer");
+const status = document.getElementById("status");
+const levelSelect = document.getElementById("level");
+
+let game = null;
+
+function renderField(cell, value) {
+  cell.className = "";
+  cell.textContent = "";
+  switch (value) {
+    case CLOSED:
+      cell.classList.add("closed");
+      break;
+    case FLAGGED:
+      cell.classList.add("closed");
+      cell.textContent = "\u{1F6A9}";
+      break;
+    case MINE:
+      cell.classList.add("mine");
+      cell.textContent = "\u{1F4A3}";
+      break;
+    case VOID:
+      cell.classList.add("void");
+      break;
+    default:
+      cell.classList.add("opened", `value-${value}`);
+      if (value > 0) {
+        cell.textContent = value;
+      }
+  }
+}
+
+function render() {
+  const values = game.fieldValues();
+  for (let row = 0; row < game.height; row++) {
+    for (let col = 0; col < game.width; col++) {
+      renderField(board.rows[row].cells[col], values[row * game.width + col]);
+    }
+  }
+  switch (game.state) {
+    case GameState.Won:
+      status.textContent = "You won!";
+      break;
+    case GameState.Lost:
+      status.textContent = "You lost!";
+      break;
+    default:
+      status.textContent = "";
+  }
+}
+
+function execute(action) {
+  try {
+    action();
+  } catch (error) {
+    status.textContent = error;
+    return;
+  }
+  render();
+}
+
+function createBoard() {
+  board.innerHTML = "";
+  for (let row = 0; row < game.height; row++) {
+    const tableRow = board.insertRow();
+    for (let col = 0; col < game.width; col++) {
+      const cell = tableRow.insertCell();
+      cell.addEventListener("click", () => execute(() => game.open(row, col)));
+      cell.addEventListener("auxclick", (event) => {
+        if (event.button === 1) {
+          execute(() => game.openNeighbors(row, col));
+        }
+      });
+      cell.addEventListener("contextmenu", (event) => {
+        event.preventDefault();
+        execute(() => game.toggleFlag(row, col));
+      });
+    }
+  }
+}
+
+function newGame() {
+  if (game !== null) {
+    game.free();
+  }
+  game = new Game(GameLevel[levelSelect.value]);
+  createBoard();
+  render();
+}
+
+function updateTimer() {
+  if (game !== null) {
+    const state = game.state;
+    game.updateTimer();
+    timer.textContent = (game.elapsedMilliseconds() / 1000).toFixed(1);
+    if (state !== game.state) {
+      render();
+    }
+  }
+}
+
+await init();
+document.getElementById("new-game").addEventListener("click", newGame);
+setInterval(updateTimer, 100);
+newGame();
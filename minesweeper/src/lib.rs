@@ -1,5 +1,17 @@
 mod minesweeper_logic;
 pub use minesweeper_logic::basic_types::SizeType;
-pub use minesweeper_logic::field_info::{FieldInfo, FieldState, FieldType};
-pub use minesweeper_logic::game::{Game, GameLevel};
+pub use minesweeper_logic::clock::{Clock, ManualClock, MonotonicClock};
+pub use minesweeper_logic::clue_rule::{
+    ClueRule, CrossClueRule, LiarClueRule, MineColor, PartialClueRule, StandardClueRule,
+};
+pub use minesweeper_logic::field_info::{
+    FieldInfo, FieldState, FieldType, CLOSED_VALUE, FLAGGED_VALUE, MINE_VALUE, VOID_VALUE,
+};
+pub use minesweeper_logic::game::{
+    Game, GameLevel, GameState, LossReason, GAME_IS_NOT_PAUSED_ERROR, GAME_IS_NOT_RUNNING_ERROR,
+    GAME_IS_PAUSED_ERROR, INVALID_MOVE_LIMIT_ERROR,
+};
+pub use minesweeper_logic::listener::GameListener;
+pub use minesweeper_logic::mask::{Mask, TOO_MUCH_FIELDS};
 pub use minesweeper_logic::results::{FlagResult, OpenInfo, OpenResult};
+pub use minesweeper_logic::{TOO_FEW_MINES_ERROR, TOO_MUCH_MINES_ERROR};
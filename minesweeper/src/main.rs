@@ -3,19 +3,25 @@ use minesweeper::{FieldType, FlagResult, Game, GameLevel, OpenResult, SizeType};
 use std::io;
 use std::vec::Vec;
 
-fn create_empty_field(width: SizeType, height: SizeType) -> Vec<Vec<char>> {
+mod protocol;
+
+fn create_empty_field(game: &Game) -> Vec<Vec<char>> {
     let mut fields = Vec::new();
-    for _r in 0..height {
+    for r in 0..game.height() {
         let mut row = Vec::new();
-        for _c in 0..width {
-            row.push('O');
+        for c in 0..game.width() {
+            let field_info = game
+                .get_field_info(r, c)
+                .expect("Unable to get field info!");
+            // The void fields are drawn like in the masks
+            row.push(if field_info.state.is_void() { '.' } else { 'O' });
         }
         fields.push(row);
     }
-    return fields;
+    fields
 }
 
-fn print_fields(fields: &Vec<Vec<char>>) {
+fn print_fields(fields: &[Vec<char>]) {
     let col_count = fields[0].len();
     let print_horizontal_line = || {
         for _col in 0..col_count * 2 + 3 {
@@ -30,14 +36,12 @@ fn print_fields(fields: &Vec<Vec<char>>) {
     }
     println!();
     print_horizontal_line();
-    let mut row_id = 0;
-    for row in fields.iter() {
+    for (row_id, row) in fields.iter().enumerate() {
         print!("{} |", row_id);
-        row_id = row_id + 1;
         for cell in row.iter() {
             print!(" {}", cell);
         }
-        println!("");
+        println!();
     }
     print_horizontal_line();
 }
@@ -57,9 +61,16 @@ enum Action {
 }
 
 fn main() {
+    // The games can be driven by other programs via the line based protocol
+    if std::env::args().skip(1).any(|arg| arg == "--protocol") {
+        let stdin = io::stdin();
+        protocol::run(stdin.lock(), io::stdout()).expect("Unable to communicate!");
+        return;
+    }
+
     let mut g = Game::new(GameLevel::Beginner);
     let mut last_result = OpenResult::Ok;
-    let mut fields = create_empty_field(g.width(), g.height());
+    let mut fields = create_empty_field(&g);
     while last_result != OpenResult::WINNER && last_result != OpenResult::Boom {
         print_fields(&fields);
 
@@ -83,18 +94,18 @@ fn main() {
                 _ => return Err("The possible actions are *o*pen and *f*lag!"),
             };
             let row_result = inputs[1].trim().parse::<SizeType>();
-            if !row_result.is_ok() {
+            if row_result.is_err() {
                 return Err("Row id is not parsable!");
             }
             let col_result = inputs[2].trim().parse::<SizeType>();
-            if !col_result.is_ok() {
+            if col_result.is_err() {
                 return Err("Col id is not parsable!");
             }
             Ok((action, row_result.unwrap(), col_result.unwrap()))
         };
 
         let mut read_result = read_input();
-        while !read_result.is_ok() {
+        while read_result.is_err() {
             eprintln!("{}", read_result.unwrap_err());
             read_result = read_input();
         }
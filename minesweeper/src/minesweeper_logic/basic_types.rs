@@ -1,4 +1,4 @@
-#[cfg(not(target_pointer_width = "64"))]
-compile_error!("This crate can only be used on 64-bit systems.");
+#[cfg(not(any(target_pointer_width = "64", target_arch = "wasm32")))]
+compile_error!("This crate can only be used on 64-bit systems and on WebAssembly.");
 
 pub type SizeType = i64;
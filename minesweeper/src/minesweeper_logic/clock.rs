@@ -0,0 +1,132 @@
+use lazy_static::lazy_static;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    static ref MONOTONIC_ORIGIN: Instant = Instant::now();
+}
+
+// A clock returns a monotonic timestamp, which is the time elapsed since an arbitrary but fixed
+// point in time
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+// Measures the time since the first use of any MonotonicClock in the process
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct MonotonicClock;
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> Duration {
+        MONOTONIC_ORIGIN.elapsed()
+    }
+}
+
+// The time of this clock only changes when it is advanced explicitly. The clones share the
+// time, so a clone can be used to control the clock after it is moved into a game.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+#[derive(Debug)]
+pub struct Stopwatch {
+    clock: Box<dyn Clock>,
+    start_time: Option<Duration>,
+    elapsed_duration: Duration,
+}
+
+impl Stopwatch {
+    pub fn new(clock: Box<dyn Clock>) -> Stopwatch {
+        Stopwatch {
+            clock,
+            start_time: None,
+            elapsed_duration: Duration::new(0, 0),
+        }
+    }
+
+    pub fn start(&mut self) {
+        if self.start_time.is_none() {
+            self.start_time = Some(self.clock.now());
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(start_time) = self.start_time.take() {
+            self.elapsed_duration += self.clock.now() - start_time;
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.start_time {
+            Some(start_time) => self.elapsed_duration + (self.clock.now() - start_time),
+            None => self.elapsed_duration,
+        }
+    }
+
+    pub fn now(&self) -> Duration {
+        self.clock.now()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn monotonic_clock_does_not_go_backwards() {
+        let first = MonotonicClock.now();
+        let second = MonotonicClock.now();
+        assert!(first <= second);
+    }
+
+    #[test]
+    fn manual_clock_is_shared_between_clones() {
+        let clock = ManualClock::new();
+        let other = clock.clone();
+        assert_eq!(Duration::from_secs(0), other.now());
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(Duration::from_millis(1500), other.now());
+    }
+
+    #[test]
+    fn stopwatch_accumulates_intervals() {
+        let clock = ManualClock::new();
+        let mut stopwatch = Stopwatch::new(Box::new(clock.clone()));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(Duration::from_secs(0), stopwatch.elapsed());
+
+        stopwatch.start();
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(Duration::from_secs(2), stopwatch.elapsed());
+        stopwatch.stop();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(Duration::from_secs(2), stopwatch.elapsed());
+
+        stopwatch.start();
+        stopwatch.start();
+        clock.advance(Duration::from_secs(4));
+        stopwatch.stop();
+        stopwatch.stop();
+        assert_eq!(Duration::from_secs(6), stopwatch.elapsed());
+    }
+}
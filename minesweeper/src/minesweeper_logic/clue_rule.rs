@@ -0,0 +1,326 @@
+use super::basic_types::SizeType;
+use super::mask::Mask;
+use std::collections::HashSet;
+use std::fmt::Debug;
+use strum_macros::Display;
+
+const MIN_VALUE: u8 = 0;
+const MAX_VALUE: u8 = 8;
+
+const NEIGHBOR_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const CROSS_OFFSETS: [(i8, i8); 8] = [
+    (-2, 0),
+    (-1, 0),
+    (1, 0),
+    (2, 0),
+    (0, -2),
+    (0, -1),
+    (0, 1),
+    (0, 2),
+];
+
+// A clue rule determines which fields are counted by the clue of a field and how the mines among
+// them are turned into the displayed value. The clue fields are also used to open the neighbors
+// of empty fields. The returned value must be between 0 and 8.
+//
+// The clue fields must be symmetric: if a field is a clue field of an other one, then the other
+// one is a clue field of it too, so only the fields around a moved mine have to be updated. The
+// value might depend on the random number of the field, which is drawn once when the table is
+// generated, so the same seed always results in the same clues.
+//
+// When the neighbors of a numbered field are opened, the flags among its chord fields are turned
+// into a value the same way as the mines, and the chord fields are opened if the value equals the
+// value of the field. The chord fields are the clue fields by default, None disables chording.
+pub trait ClueRule: Debug + Send + Sync {
+    fn get_clue_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> HashSet<(SizeType, SizeType)>;
+
+    fn get_value(
+        &self,
+        row: SizeType,
+        col: SizeType,
+        mines_in_clue_fields: &HashSet<(SizeType, SizeType)>,
+        random_number: u32,
+    ) -> u8;
+
+    fn get_chord_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> Option<HashSet<(SizeType, SizeType)>> {
+        Some(self.get_clue_fields(mask, row, col))
+    }
+}
+
+fn get_fields_with_offsets(
+    mask: &Mask,
+    row: SizeType,
+    col: SizeType,
+    offsets: &[(i8, i8)],
+) -> HashSet<(SizeType, SizeType)> {
+    let mut fields = HashSet::new();
+
+    for offset in offsets {
+        match (
+            row.checked_add(offset.0 as SizeType),
+            col.checked_add(offset.1 as SizeType),
+        ) {
+            (Some(r), Some(c)) if mask.is_present(r, c) => {
+                fields.insert((r, c));
+            }
+            _ => (),
+        }
+    }
+
+    fields
+}
+
+pub fn get_neighbor_fields(
+    mask: &Mask,
+    row: SizeType,
+    col: SizeType,
+) -> HashSet<(SizeType, SizeType)> {
+    get_fields_with_offsets(mask, row, col, &NEIGHBOR_OFFSETS)
+}
+
+// The classic rule: the number of mines among the eight neighbors
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct StandardClueRule;
+
+impl ClueRule for StandardClueRule {
+    fn get_clue_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> HashSet<(SizeType, SizeType)> {
+        get_neighbor_fields(mask, row, col)
+    }
+
+    fn get_value(
+        &self,
+        _row: SizeType,
+        _col: SizeType,
+        mines_in_clue_fields: &HashSet<(SizeType, SizeType)>,
+        _random_number: u32,
+    ) -> u8 {
+        mines_in_clue_fields.len() as u8
+    }
+}
+
+// The number of mines in the same row and column at most two fields away
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct CrossClueRule;
+
+impl ClueRule for CrossClueRule {
+    fn get_clue_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> HashSet<(SizeType, SizeType)> {
+        get_fields_with_offsets(mask, row, col, &CROSS_OFFSETS)
+    }
+
+    fn get_value(
+        &self,
+        _row: SizeType,
+        _col: SizeType,
+        mines_in_clue_fields: &HashSet<(SizeType, SizeType)>,
+        _random_number: u32,
+    ) -> u8 {
+        mines_in_clue_fields.len() as u8
+    }
+}
+
+// Every clue is off by exactly one from the number of mines among the eight neighbors, the random
+// number of the field decides the direction when both are possible
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Default)]
+pub struct LiarClueRule;
+
+impl ClueRule for LiarClueRule {
+    fn get_clue_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> HashSet<(SizeType, SizeType)> {
+        get_neighbor_fields(mask, row, col)
+    }
+
+    fn get_value(
+        &self,
+        _row: SizeType,
+        _col: SizeType,
+        mines_in_clue_fields: &HashSet<(SizeType, SizeType)>,
+        random_number: u32,
+    ) -> u8 {
+        let real_value = mines_in_clue_fields.len() as u8;
+        if real_value == MIN_VALUE || (real_value < MAX_VALUE && random_number.is_multiple_of(2)) {
+            real_value + 1
+        } else {
+            real_value - 1
+        }
+    }
+
+    // The value doesn't tell the number of mines, so the flags can't be checked against it
+    fn get_chord_fields(
+        &self,
+        _mask: &Mask,
+        _row: SizeType,
+        _col: SizeType,
+    ) -> Option<HashSet<(SizeType, SizeType)>> {
+        None
+    }
+}
+
+// The fields of the table are colored like a chessboard, the color of a mine is the color of its
+// field. Light fields are the ones where the sum of the row and column index is even.
+#[derive(Clone, Copy, Eq, PartialEq, Display, Debug)]
+pub enum MineColor {
+    Light,
+    Dark,
+}
+
+impl MineColor {
+    pub fn of_field(row: SizeType, col: SizeType) -> MineColor {
+        if (row + col) % 2 == 0 {
+            MineColor::Light
+        } else {
+            MineColor::Dark
+        }
+    }
+}
+
+// Only the mines with the counted color are included in the number of mines among the eight
+// neighbors
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct PartialClueRule {
+    pub counted_color: MineColor,
+}
+
+impl ClueRule for PartialClueRule {
+    fn get_clue_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> HashSet<(SizeType, SizeType)> {
+        get_neighbor_fields(mask, row, col)
+    }
+
+    fn get_value(
+        &self,
+        _row: SizeType,
+        _col: SizeType,
+        mines_in_clue_fields: &HashSet<(SizeType, SizeType)>,
+        _random_number: u32,
+    ) -> u8 {
+        mines_in_clue_fields
+            .iter()
+            .filter(|&&(r, c)| MineColor::of_field(r, c) == self.counted_color)
+            .count() as u8
+    }
+
+    // The value doesn't say anything about the fields with the other color, so only the fields
+    // with the counted color can be opened safely
+    fn get_chord_fields(
+        &self,
+        mask: &Mask,
+        row: SizeType,
+        col: SizeType,
+    ) -> Option<HashSet<(SizeType, SizeType)>> {
+        Some(
+            get_neighbor_fields(mask, row, col)
+                .into_iter()
+                .filter(|&(r, c)| MineColor::of_field(r, c) == self.counted_color)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_set(fields: &[(SizeType, SizeType)]) -> HashSet<(SizeType, SizeType)> {
+        fields.iter().cloned().collect()
+    }
+
+    #[test]
+    fn standard_clue_fields() {
+        let mask = Mask::new(3, 3).unwrap();
+        let rule = StandardClueRule;
+        assert_eq!(8, rule.get_clue_fields(&mask, 1, 1).len());
+        assert_eq!(
+            to_set(&[(0, 1), (1, 0), (1, 1)]),
+            rule.get_clue_fields(&mask, 0, 0)
+        );
+        assert_eq!(2, rule.get_value(0, 0, &to_set(&[(0, 1), (1, 1)]), 0));
+    }
+
+    #[test]
+    fn cross_clue_fields() {
+        let mask = Mask::new(5, 5).unwrap();
+        let rule = CrossClueRule;
+        assert_eq!(
+            to_set(&[
+                (0, 2),
+                (1, 2),
+                (3, 2),
+                (4, 2),
+                (2, 0),
+                (2, 1),
+                (2, 3),
+                (2, 4)
+            ]),
+            rule.get_clue_fields(&mask, 2, 2)
+        );
+        assert_eq!(
+            to_set(&[(1, 0), (2, 0), (0, 1), (0, 2)]),
+            rule.get_clue_fields(&mask, 0, 0)
+        );
+    }
+
+    #[test]
+    fn liar_is_off_by_one() {
+        let rule = LiarClueRule;
+        let all_neighbors = get_neighbor_fields(&Mask::new(3, 3).unwrap(), 1, 1);
+        let mines = to_set(&[(0, 0), (0, 1), (0, 2)]);
+        for random_number in 0..2 {
+            assert_eq!(1, rule.get_value(0, 0, &HashSet::new(), random_number));
+            assert_eq!(7, rule.get_value(1, 1, &all_neighbors, random_number));
+        }
+        assert_eq!(4, rule.get_value(1, 1, &mines, 0));
+        assert_eq!(2, rule.get_value(1, 1, &mines, 1));
+    }
+
+    #[test]
+    fn partial_counts_only_one_color() {
+        let mines = to_set(&[(0, 0), (0, 1), (1, 0), (2, 2)]);
+        let light_rule = PartialClueRule {
+            counted_color: MineColor::Light,
+        };
+        let dark_rule = PartialClueRule {
+            counted_color: MineColor::Dark,
+        };
+        assert_eq!(2, light_rule.get_value(1, 1, &mines, 0));
+        assert_eq!(2, dark_rule.get_value(1, 1, &mines, 0));
+        assert_eq!(0, dark_rule.get_value(1, 1, &to_set(&[(2, 2)]), 0));
+    }
+}
@@ -1,11 +1,19 @@
 use strum_macros::Display;
 
+// The values of the board snapshots of the bindings, the opened numbered and empty fields are
+// represented by their values
+pub const CLOSED_VALUE: i8 = -1;
+pub const FLAGGED_VALUE: i8 = -2;
+pub const MINE_VALUE: i8 = -3;
+pub const VOID_VALUE: i8 = -4;
+
 #[repr(C)]
 #[derive(Clone, Copy, Eq, PartialEq, Display, Debug)]
 pub enum FieldState {
     Closed,
     Opened,
     Flagged,
+    Void,
 }
 
 impl FieldState {
@@ -16,6 +24,10 @@ impl FieldState {
     pub fn is_flagged(&self) -> bool {
         self == &FieldState::Flagged
     }
+
+    pub fn is_void(&self) -> bool {
+        self == &FieldState::Void
+    }
 }
 
 #[repr(C)]
@@ -39,9 +51,14 @@ impl FieldType {
 
     #[allow(dead_code)]
     pub fn is_numbered(&self) -> bool {
+        matches!(self, FieldType::Numbered(_))
+    }
+
+    pub fn to_value(&self) -> i8 {
         match self {
-            FieldType::Numbered(_) => true,
-            _ => false,
+            FieldType::Empty => 0,
+            FieldType::Numbered(value) => *value as i8,
+            FieldType::Mine => MINE_VALUE,
         }
     }
 }
@@ -52,3 +69,48 @@ pub struct FieldInfo {
     pub state: FieldState,
     pub field_type: FieldType,
 }
+
+impl FieldInfo {
+    pub fn to_value(&self) -> i8 {
+        match self.state {
+            FieldState::Closed => CLOSED_VALUE,
+            FieldState::Flagged => FLAGGED_VALUE,
+            FieldState::Void => VOID_VALUE,
+            FieldState::Opened => self.field_type.to_value(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values() {
+        let field_info = |state, field_type| FieldInfo { state, field_type };
+        assert_eq!(
+            CLOSED_VALUE,
+            field_info(FieldState::Closed, FieldType::Mine).to_value()
+        );
+        assert_eq!(
+            FLAGGED_VALUE,
+            field_info(FieldState::Flagged, FieldType::Empty).to_value()
+        );
+        assert_eq!(
+            VOID_VALUE,
+            field_info(FieldState::Void, FieldType::Empty).to_value()
+        );
+        assert_eq!(
+            MINE_VALUE,
+            field_info(FieldState::Opened, FieldType::Mine).to_value()
+        );
+        assert_eq!(
+            0,
+            field_info(FieldState::Opened, FieldType::Empty).to_value()
+        );
+        assert_eq!(
+            5,
+            field_info(FieldState::Opened, FieldType::Numbered(5)).to_value()
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use super::basic_types::SizeType;
+use super::field_info::FieldType;
+use super::game::LossReason;
+use super::results::FlagResult;
+use std::time::Duration;
+
+// The listener of a game is notified synchronously from the operations of the game. Every method
+// has an empty default implementation, so only the interesting events have to be implemented.
+pub trait GameListener: Send {
+    fn on_field_opened(&mut self, _row: SizeType, _col: SizeType, _field_type: FieldType) {}
+
+    fn on_field_flagged(&mut self, _row: SizeType, _col: SizeType, _flag_result: FlagResult) {}
+
+    fn on_game_won(&mut self) {}
+
+    fn on_game_lost(&mut self, _reason: LossReason) {}
+
+    // Called when the elapsed time reaches a new whole second
+    fn on_timer_tick(&mut self, _elapsed: Duration) {}
+}
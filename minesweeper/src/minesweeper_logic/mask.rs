@@ -0,0 +1,161 @@
+use super::basic_types::SizeType;
+use std::convert::TryFrom;
+
+pub static TOO_MUCH_FIELDS: &str = "Too much fields!";
+static INVALID_MASK_ERROR: &str = "Invalid mask!";
+static EMPTY_MASK_ERROR: &str = "Mask does not contain any field!";
+
+const PRESENT_FIELD_CHAR: char = '#';
+const VOID_FIELD_CHAR: char = '.';
+
+pub fn check_number_of_fields(height: SizeType, width: SizeType) -> Result<(), &'static str> {
+    let numnber_of_fields = match width.checked_mul(height) {
+        Some(x) => x,
+        _ => return Err(TOO_MUCH_FIELDS),
+    };
+
+    // range is always -(2^X) ... 2^X-1, so abs(SizeType::MIN) > SizeType::MAX,
+    // therefore this will also make sure the remaining mine count wont be too less. On 32-bit
+    // targets (e.g. WebAssembly) the fields also have to be indexable by usize.
+    let max_number_of_fields = SizeType::try_from(usize::MAX).unwrap_or(SizeType::MAX);
+
+    if max_number_of_fields < numnber_of_fields {
+        Err(TOO_MUCH_FIELDS)
+    } else {
+        Ok(())
+    }
+}
+
+// Void fields (the ones not present in the mask) have no neighbors and never contain mines
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Mask {
+    height: SizeType,
+    width: SizeType,
+    number_of_present_fields: SizeType,
+    cells: Vec<Vec<bool>>,
+}
+
+impl Mask {
+    pub fn new(height: SizeType, width: SizeType) -> Result<Mask, &'static str> {
+        check_number_of_fields(height, width)?;
+        if height <= 0 || width <= 0 {
+            return Err(EMPTY_MASK_ERROR);
+        }
+
+        Ok(Mask {
+            height,
+            width,
+            number_of_present_fields: height * width,
+            cells: vec![vec![true; width as usize]; height as usize],
+        })
+    }
+
+    pub fn from_cells(cells: Vec<Vec<bool>>) -> Result<Mask, &'static str> {
+        let height = cells.len() as SizeType;
+        let width = match cells.first() {
+            Some(row) => row.len() as SizeType,
+            None => return Err(EMPTY_MASK_ERROR),
+        };
+        check_number_of_fields(height, width)?;
+
+        if cells.iter().any(|row| row.len() as SizeType != width) {
+            return Err(INVALID_MASK_ERROR);
+        }
+
+        let number_of_present_fields = cells
+            .iter()
+            .map(|row| row.iter().filter(|&&is_present| is_present).count() as SizeType)
+            .sum();
+
+        if number_of_present_fields == 0 {
+            return Err(EMPTY_MASK_ERROR);
+        }
+
+        Ok(Mask {
+            height,
+            width,
+            number_of_present_fields,
+            cells,
+        })
+    }
+
+    // e.g. [".#.", "###", ".#."] is a small cross
+    pub fn from_rows(rows: &[&str]) -> Result<Mask, &'static str> {
+        let mut cells = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut cells_row = Vec::with_capacity(row.len());
+            for character in row.chars() {
+                match character {
+                    PRESENT_FIELD_CHAR => cells_row.push(true),
+                    VOID_FIELD_CHAR => cells_row.push(false),
+                    _ => return Err(INVALID_MASK_ERROR),
+                }
+            }
+            cells.push(cells_row);
+        }
+        Mask::from_cells(cells)
+    }
+
+    pub fn height(&self) -> SizeType {
+        self.height
+    }
+
+    pub fn width(&self) -> SizeType {
+        self.width
+    }
+
+    pub fn number_of_present_fields(&self) -> SizeType {
+        self.number_of_present_fields
+    }
+
+    pub fn is_inside(&self, row: SizeType, col: SizeType) -> bool {
+        row >= 0 && row < self.height && col >= 0 && col < self.width
+    }
+
+    pub fn is_present(&self, row: SizeType, col: SizeType) -> bool {
+        self.is_inside(row, col) && self.cells[row as usize][col as usize]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn full_mask() {
+        let mask = Mask::new(3, 4).unwrap();
+        assert_eq!(3, mask.height());
+        assert_eq!(4, mask.width());
+        assert_eq!(12, mask.number_of_present_fields());
+        for row in 0..3 {
+            for col in 0..4 {
+                assert!(mask.is_present(row, col));
+            }
+        }
+        assert!(!mask.is_present(-1, 0));
+        assert!(!mask.is_present(0, 4));
+        assert!(!mask.is_present(3, 0));
+    }
+
+    #[test]
+    fn mask_from_rows() {
+        let mask = Mask::from_rows(&["###", "#.#", "###"]).unwrap();
+        assert_eq!(3, mask.height());
+        assert_eq!(3, mask.width());
+        assert_eq!(8, mask.number_of_present_fields());
+        assert!(mask.is_inside(1, 1));
+        assert!(!mask.is_present(1, 1));
+        assert!(mask.is_present(0, 0));
+        assert!(mask.is_present(2, 2));
+    }
+
+    #[test]
+    fn invalid_masks() {
+        assert_eq!(Err(EMPTY_MASK_ERROR), Mask::from_rows(&[]));
+        assert_eq!(Err(EMPTY_MASK_ERROR), Mask::from_rows(&["...", "..."]));
+        assert_eq!(Err(INVALID_MASK_ERROR), Mask::from_rows(&["##", "###"]));
+        assert_eq!(Err(INVALID_MASK_ERROR), Mask::from_rows(&["#x#"]));
+        assert_eq!(Err(EMPTY_MASK_ERROR), Mask::new(0, 5));
+        assert_eq!(Err(TOO_MUCH_FIELDS), Mask::new(SizeType::MAX, 2));
+    }
+}
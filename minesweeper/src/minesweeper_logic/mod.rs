@@ -1,5 +1,10 @@
 pub mod basic_types;
+pub mod clock;
+pub mod clue_rule;
 pub mod field_info;
 pub mod game;
+pub mod listener;
+pub mod mask;
 pub mod results;
 mod table;
+pub use table::{TOO_FEW_MINES_ERROR, TOO_MUCH_MINES_ERROR};
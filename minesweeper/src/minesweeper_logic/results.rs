@@ -18,6 +18,8 @@ pub enum OpenResult {
     IsFlagged,
     Boom,
     WINNER,
+    TimeIsUp,
+    OutOfMoves,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -1,19 +1,24 @@
 use super::basic_types::SizeType;
+use super::clue_rule::{ClueRule, StandardClueRule};
 use super::field_info::{FieldInfo, FieldState, FieldType};
+use super::mask::Mask;
 use super::results::{FlagResult, OpenInfo, OpenResult};
 use indexmap::IndexSet;
 use mockall::automock;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use strum_macros::Display;
 
-static INVALID_VALUE_ERROR: &'static str = "Invalid value!";
-static INVALID_INDEX_ERROR: &'static str = "Invalid index!";
-static INVALID_MINE_LOCATIONS: &'static str = "Invalid mine locations!";
-static TOO_MUCH_FIELDS: &'static str = "Too much fields!";
-static TOO_MUCH_MINES_ERROR: &'static str = "Too much mines!";
-static TOO_FEW_MINES_ERROR: &'static str = "Too few mines!";
-static MINE_DOES_NOT_HAVE_VALUE_ERROR: &'static str = "Mine does not have value!";
-static OPENED_FIELD_CAN_NOT_BE_UPDATED_ERROR: &'static str = "An opened field can not be updated!";
+static INVALID_VALUE_ERROR: &str = "Invalid value!";
+static INVALID_INDEX_ERROR: &str = "Invalid index!";
+static INVALID_MINE_LOCATIONS: &str = "Invalid mine locations!";
+pub static TOO_MUCH_MINES_ERROR: &str = "Too much mines!";
+pub static TOO_FEW_MINES_ERROR: &str = "Too few mines!";
+static MINE_DOES_NOT_HAVE_VALUE_ERROR: &str = "Mine does not have value!";
+static OPENED_FIELD_CAN_NOT_BE_UPDATED_ERROR: &str = "An opened field can not be updated!";
+static VOID_FIELD_ERROR: &str = "Void field does not exist!";
 
 trait Field {
     fn get_field_state(&self) -> FieldState;
@@ -22,13 +27,17 @@ trait Field {
 }
 
 #[automock]
-pub trait Table {
+pub trait Table: Send {
     fn width(&self) -> SizeType;
     fn height(&self) -> SizeType;
+    fn number_of_mines(&self) -> SizeType;
+    // An open never reports more fields than this, a boom reports every existing field
+    fn max_number_of_newly_opened_fields(&self) -> usize;
     fn get_field_info(&self, row: SizeType, col: SizeType) -> Result<FieldInfo, &'static str>;
     fn open_field(&mut self, row: SizeType, col: SizeType) -> Result<OpenInfo, &'static str>;
     fn open_neighbors(&mut self, row: SizeType, col: SizeType) -> Result<OpenInfo, &'static str>;
     fn toggle_flag(&mut self, row: SizeType, col: SizeType) -> Result<FlagResult, &'static str>;
+    fn clone_box(&self) -> Box<dyn Table>;
 }
 
 #[derive(Clone, Copy, Eq, PartialEq, Display, Debug)]
@@ -38,6 +47,7 @@ enum FieldOpenResult {
     MultiOpen,
     Boom,
     IsFlagged,
+    Void,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -67,6 +77,15 @@ impl FieldInner {
         FieldInner::new_with_field_type(FieldType::Empty)
     }
 
+    fn new_void() -> FieldInner {
+        FieldInner {
+            field_info: FieldInfo {
+                state: FieldState::Void,
+                field_type: FieldType::Empty,
+            },
+        }
+    }
+
     fn new_numbered(value: u8) -> Result<FieldInner, &'static str> {
         if !FieldInner::is_valid_value(value) {
             Err(INVALID_VALUE_ERROR)
@@ -113,7 +132,9 @@ impl FieldInner {
     }
 
     fn open(&mut self) -> FieldOpenResult {
-        if self.field_info.state.is_flagged() {
+        if self.field_info.state.is_void() {
+            FieldOpenResult::Void
+        } else if self.field_info.state.is_flagged() {
             FieldOpenResult::IsFlagged
         } else if self.field_info.state.is_opened() {
             FieldOpenResult::AlreadyOpened
@@ -144,7 +165,7 @@ impl Field for FieldInner {
     }
 
     fn get_field_type(&self) -> FieldType {
-        self.field_info.field_type.clone()
+        self.field_info.field_type
     }
 
     fn get_public_field_info(&self) -> FieldInfo {
@@ -158,37 +179,42 @@ impl Field for FieldInner {
     }
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 struct FieldVisiter {
-    height: SizeType,
-    width: SizeType,
+    mask: Arc<Mask>,
+    clue_rule: Arc<dyn ClueRule>,
     fields_to_visit: IndexSet<(SizeType, SizeType)>,
     visited_fields: HashSet<(SizeType, SizeType)>,
 }
 
 impl FieldVisiter {
     fn new(
-        height: SizeType,
-        width: SizeType,
+        mask: Arc<Mask>,
+        clue_rule: Arc<dyn ClueRule>,
         row: SizeType,
         col: SizeType,
     ) -> Result<FieldVisiter, &'static str> {
         let mut fields_to_visit = IndexSet::new();
         fields_to_visit.insert((row, col));
         Ok(FieldVisiter {
-            height,
-            width,
+            mask,
+            clue_rule,
             fields_to_visit,
             visited_fields: HashSet::new(),
         })
     }
 
     fn extend_with_unvisited_neighbors(&mut self, row: SizeType, col: SizeType) {
-        let fields_to_extend: HashSet<(SizeType, SizeType)> =
-            get_neighbor_fields(self.height, self.width, row, col)
-                .difference(&self.visited_fields)
-                .cloned()
-                .collect();
+        let neighbors = self.clue_rule.get_clue_fields(&self.mask, row, col);
+        self.extend_with_unvisited_fields(&neighbors);
+    }
+
+    fn extend_with_unvisited_fields(&mut self, fields: &HashSet<(SizeType, SizeType)>) {
+        let mut fields_to_extend: Vec<(SizeType, SizeType)> =
+            fields.difference(&self.visited_fields).cloned().collect();
+        // The order of the visited fields must not depend on the order of the hash set, otherwise
+        // the mines could be moved differently in games with the same seed
+        fields_to_extend.sort_unstable();
         self.fields_to_visit.extend(fields_to_extend);
     }
 
@@ -204,42 +230,8 @@ impl FieldVisiter {
     }
 }
 
-const NEIGHBOR_OFFSETS: [(i8, i8); 8] = [
-    (-1, -1),
-    (0, -1),
-    (1, -1),
-    (-1, 0),
-    (1, 0),
-    (-1, 1),
-    (0, 1),
-    (1, 1),
-];
-
-fn check_number_of_fields(height: SizeType, width: SizeType) -> Result<(), &'static str> {
-    let numnber_of_fields = match width.checked_mul(height) {
-        Some(x) => x,
-        _ => return Err(TOO_MUCH_FIELDS),
-    };
-
-    // range is always -(2^X) ... 2^X-1, so abs(SizeType::MIN) > SizeType::MAX,
-    // therefore this will also make sure the remaining mine count wont be too less
-    let max_number_of_fields = SizeType::MAX;
-
-    if max_number_of_fields < numnber_of_fields {
-        Err(TOO_MUCH_FIELDS)
-    } else {
-        Ok(())
-    }
-}
-
-fn check_sizes(
-    height: SizeType,
-    width: SizeType,
-    number_of_mines: SizeType,
-) -> Result<(), &'static str> {
-    check_number_of_fields(height, width)?;
-
-    let max_number_of_mines = width * height - 1 as SizeType;
+fn check_number_of_mines(mask: &Mask, number_of_mines: SizeType) -> Result<(), &'static str> {
+    let max_number_of_mines = mask.number_of_present_fields() - 1 as SizeType;
     let min_number_of_mines = 1;
 
     if max_number_of_mines < number_of_mines {
@@ -251,122 +243,167 @@ fn check_sizes(
     }
 }
 
-fn generate_mine_locations(
-    height: SizeType,
-    width: SizeType,
+fn generate_mine_locations<R: Rng + ?Sized>(
+    mask: &Mask,
     number_of_mines: SizeType,
+    rng: &mut R,
 ) -> Result<HashSet<(SizeType, SizeType)>, &'static str> {
+    check_number_of_mines(mask, number_of_mines)?;
     let mut mine_locations = HashSet::new();
     while (mine_locations.len() as SizeType) < number_of_mines {
-        mine_locations.insert((
-            rand::random::<SizeType>().abs() % height,
-            rand::random::<SizeType>().abs() % width,
-        ));
+        let row = rng.gen::<SizeType>().abs() % mask.height();
+        let col = rng.gen::<SizeType>().abs() % mask.width();
+        if mask.is_present(row, col) {
+            mine_locations.insert((row, col));
+        }
     }
     Ok(mine_locations)
 }
 
-fn get_neighbor_fields(
-    height: SizeType,
-    width: SizeType,
+fn get_mines_in_clue_fields(
+    mask: &Mask,
+    clue_rule: &dyn ClueRule,
     row: SizeType,
     col: SizeType,
+    mine_locations: &HashSet<(SizeType, SizeType)>,
 ) -> HashSet<(SizeType, SizeType)> {
-    let mut neighbors = HashSet::new();
-
-    for offset in &NEIGHBOR_OFFSETS {
-        match (
-            row.checked_add(offset.0 as i64),
-            col.checked_add(offset.1 as i64),
-        ) {
-            (Some(r), Some(c)) if r >= 0 && r < height && c >= 0 && c < width => {
-                neighbors.insert((r, c));
-            }
-            _ => (),
-        }
-    }
-
-    neighbors
+    clue_rule
+        .get_clue_fields(mask, row, col)
+        .into_iter()
+        .filter(|coords| mine_locations.contains(coords))
+        .collect()
 }
 
 fn get_field_value(
-    height: SizeType,
-    width: SizeType,
+    mask: &Mask,
+    clue_rule: &dyn ClueRule,
     row: SizeType,
     col: SizeType,
     mine_locations: &HashSet<(SizeType, SizeType)>,
+    random_number: u32,
 ) -> Result<u8, &'static str> {
     if mine_locations.contains(&(row, col)) {
         return Err(MINE_DOES_NOT_HAVE_VALUE_ERROR);
     }
-    let mut field_value: u8 = 0;
 
-    for (r, c) in get_neighbor_fields(height, width, row, col) {
-        if mine_locations.contains(&(r, c)) {
-            field_value = field_value + 1;
+    let mines_in_clue_fields = get_mines_in_clue_fields(mask, clue_rule, row, col, mine_locations);
+    Ok(clue_rule.get_value(row, col, &mines_in_clue_fields, random_number))
+}
+
+fn generate_field(
+    mask: &Mask,
+    clue_rule: &dyn ClueRule,
+    row: SizeType,
+    col: SizeType,
+    mine_locations: &HashSet<(SizeType, SizeType)>,
+    random_number: u32,
+) -> Result<FieldInner, &'static str> {
+    if !mask.is_present(row, col) {
+        Ok(FieldInner::new_void())
+    } else if mine_locations.contains(&(row, col)) {
+        Ok(FieldInner::new_mine())
+    } else {
+        match get_field_value(mask, clue_rule, row, col, mine_locations, random_number)? {
+            0 => Ok(FieldInner::new_empty()),
+            value => FieldInner::new_numbered(value),
         }
     }
+}
 
-    Ok(field_value)
+fn generate_random_numbers<R: Rng + ?Sized>(mask: &Mask, rng: &mut R) -> Vec<Vec<u32>> {
+    (0..mask.height())
+        .map(|_| (0..mask.width()).map(|_| rng.gen::<u32>()).collect())
+        .collect()
 }
 
 fn generate_fields(
-    height: SizeType,
-    width: SizeType,
+    mask: &Mask,
+    clue_rule: &dyn ClueRule,
     mine_locations: &HashSet<(SizeType, SizeType)>,
+    random_numbers: &[Vec<u32>],
 ) -> Result<Vec<Vec<FieldInner>>, &'static str> {
     let mut fields = Vec::new();
 
-    for r in 0..height {
+    for r in 0..mask.height() {
         let mut row = Vec::new();
-        for c in 0..width {
-            if mine_locations.contains(&(r, c)) {
-                row.push(FieldInner::new_mine());
-            } else {
-                let value = get_field_value(height, width, r, c, mine_locations)?;
-                if value == 0 {
-                    row.push(FieldInner::new_empty());
-                } else {
-                    row.push(FieldInner::new_numbered(value).unwrap())
-                }
-            }
+        for c in 0..mask.width() {
+            let random_number = random_numbers[r as usize][c as usize];
+            row.push(generate_field(
+                mask,
+                clue_rule,
+                r,
+                c,
+                mine_locations,
+                random_number,
+            )?);
         }
         fields.push(row);
     }
     Ok(fields)
 }
 
-#[derive(Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct BasicTable {
-    height: SizeType,
-    width: SizeType,
+    mask: Arc<Mask>,
+    clue_rule: Arc<dyn ClueRule>,
     mine_locations: HashSet<(SizeType, SizeType)>,
     number_of_opened_fields: SizeType,
     fields: Vec<Vec<FieldInner>>,
+    // The random numbers of the clue rule, they are drawn once for every field
+    random_numbers: Vec<Vec<u32>>,
 }
 
 impl BasicTable {
+    #[allow(dead_code)]
     fn with_custom_mines(
         height: SizeType,
         width: SizeType,
         mine_locations: HashSet<(SizeType, SizeType)>,
     ) -> Result<BasicTable, &'static str> {
-        check_sizes(height, width, mine_locations.len() as SizeType)?;
+        BasicTable::with_mask_and_custom_mines(
+            Mask::new(height, width)?,
+            Arc::new(StandardClueRule),
+            mine_locations,
+        )
+    }
+
+    fn with_mask_and_custom_mines(
+        mask: Mask,
+        clue_rule: Arc<dyn ClueRule>,
+        mine_locations: HashSet<(SizeType, SizeType)>,
+    ) -> Result<BasicTable, &'static str> {
+        BasicTable::with_mask_custom_mines_and_rng(
+            mask,
+            clue_rule,
+            mine_locations,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    fn with_mask_custom_mines_and_rng(
+        mask: Mask,
+        clue_rule: Arc<dyn ClueRule>,
+        mine_locations: HashSet<(SizeType, SizeType)>,
+        rng: &mut dyn RngCore,
+    ) -> Result<BasicTable, &'static str> {
+        check_number_of_mines(&mask, mine_locations.len() as SizeType)?;
 
         if mine_locations
             .iter()
-            .any(|&(row, col)| row >= height || col >= width)
+            .any(|&(row, col)| !mask.is_present(row, col))
         {
             return Err(INVALID_MINE_LOCATIONS);
         }
 
-        let fields = generate_fields(height, width, &mine_locations)?;
+        let random_numbers = generate_random_numbers(&mask, rng);
+        let fields = generate_fields(&mask, clue_rule.as_ref(), &mine_locations, &random_numbers)?;
         Ok(BasicTable {
-            height,
-            width,
+            mask: Arc::new(mask),
+            clue_rule,
             mine_locations,
             number_of_opened_fields: 0,
             fields,
+            random_numbers,
         })
     }
 
@@ -375,21 +412,105 @@ impl BasicTable {
         width: SizeType,
         number_of_mines: SizeType,
     ) -> Result<BasicTable, &'static str> {
-        let mine_locations = generate_mine_locations(height, width, number_of_mines)?;
-        BasicTable::with_custom_mines(height, width, mine_locations)
+        BasicTable::with_mask(Mask::new(height, width)?, number_of_mines)
+    }
+
+    pub fn with_mask(mask: Mask, number_of_mines: SizeType) -> Result<BasicTable, &'static str> {
+        BasicTable::with_mask_and_clue_rule(mask, number_of_mines, Arc::new(StandardClueRule))
+    }
+
+    pub fn with_mask_and_clue_rule(
+        mask: Mask,
+        number_of_mines: SizeType,
+        clue_rule: Arc<dyn ClueRule>,
+    ) -> Result<BasicTable, &'static str> {
+        let mine_locations =
+            generate_mine_locations(&mask, number_of_mines, &mut rand::thread_rng())?;
+        BasicTable::with_mask_and_custom_mines(mask, clue_rule, mine_locations)
+    }
+
+    // The same seed always results in the same mine locations
+    pub fn with_seed(
+        height: SizeType,
+        width: SizeType,
+        number_of_mines: SizeType,
+        seed: u64,
+    ) -> Result<BasicTable, &'static str> {
+        BasicTable::with_mask_clue_rule_and_seed(
+            Mask::new(height, width)?,
+            number_of_mines,
+            Arc::new(StandardClueRule),
+            seed,
+        )
+    }
+
+    // The same seed always results in the same mine locations and clues
+    pub fn with_mask_clue_rule_and_seed(
+        mask: Mask,
+        number_of_mines: SizeType,
+        clue_rule: Arc<dyn ClueRule>,
+        seed: u64,
+    ) -> Result<BasicTable, &'static str> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mine_locations = generate_mine_locations(&mask, number_of_mines, &mut rng)?;
+        BasicTable::with_mask_custom_mines_and_rng(mask, clue_rule, mine_locations, &mut rng)
+    }
+
+    fn create_visiter(&self, row: SizeType, col: SizeType) -> Result<FieldVisiter, &'static str> {
+        FieldVisiter::new(self.mask.clone(), self.clue_rule.clone(), row, col)
+    }
+
+    fn get_clue_fields(&self, row: SizeType, col: SizeType) -> HashSet<(SizeType, SizeType)> {
+        self.clue_rule.get_clue_fields(&self.mask, row, col)
+    }
+
+    fn has_mine_in_clue_fields(&self, row: SizeType, col: SizeType) -> bool {
+        self.get_clue_fields(row, col)
+            .iter()
+            .any(|coords| self.mine_locations.contains(coords))
     }
 
-    fn get_neighbor_fields(&self, row: SizeType, col: SizeType) -> HashSet<(SizeType, SizeType)> {
-        get_neighbor_fields(self.height, self.width, row, col)
+    fn recalculate_field_type(&mut self, row: SizeType, col: SizeType) -> Result<(), &'static str> {
+        if self.mine_locations.contains(&(row, col)) {
+            return self.get_field_unchecked_mut(row, col).update_type_to_mine();
+        }
+        let field_value = get_field_value(
+            &self.mask,
+            self.clue_rule.as_ref(),
+            row,
+            col,
+            &self.mine_locations,
+            self.random_numbers[row as usize][col as usize],
+        )?;
+        match field_value {
+            0 => self
+                .get_field_unchecked_mut(row, col)
+                .update_type_to_empty(),
+            _ => self
+                .get_field_unchecked_mut(row, col)
+                .update_type_with_value(field_value),
+        }
     }
 
-    fn get_field_value(&self, row: SizeType, col: SizeType) -> Result<u8, &'static str> {
-        get_field_value(self.height, self.width, row, col, &self.mine_locations)
+    // The clue fields are symmetric, so only the values of the clue fields of the moved mine
+    // change
+    fn recalculate_field_types_around(
+        &mut self,
+        moved_fields: &[(SizeType, SizeType)],
+    ) -> Result<(), &'static str> {
+        let mut fields_to_update: Vec<(SizeType, SizeType)> = moved_fields.to_vec();
+        for &(row, col) in moved_fields {
+            fields_to_update.extend(self.get_clue_fields(row, col));
+        }
+        for (row, col) in fields_to_update {
+            self.recalculate_field_type(row, col)?;
+        }
+        Ok(())
     }
 
     fn all_fields_are_open(&self) -> bool {
-        self.width.checked_mul(self.height)
-            == (self.mine_locations.len() as SizeType).checked_add(self.number_of_opened_fields)
+        self.mask.number_of_present_fields()
+            == (self.mine_locations.len() as SizeType) + self.number_of_opened_fields
     }
 
     fn get_field_unchecked(&self, row: SizeType, col: SizeType) -> &FieldInner {
@@ -406,58 +527,55 @@ impl BasicTable {
             .get_field_type()
             .is_mine()
         {
-            let mut new_place = (0, 0);
-            let mut visiter = FieldVisiter::new(self.height, self.width, row, col)?;
+            let mut new_place = None;
+            let mut visiter = self.create_visiter(row, col)?;
             while let Some((r, c)) = visiter.next() {
                 if !self.get_field_unchecked(r, c).get_field_type().is_mine() {
-                    new_place = (r, c);
+                    new_place = Some((r, c));
                     break;
                 }
                 visiter.extend_with_unvisited_neighbors(r, c);
             }
 
-            self.get_field_unchecked_mut(new_place.0, new_place.1)
-                .update_type_to_mine()
-                .unwrap();
-            self.get_field_unchecked_mut(row, col)
-                .update_type_to_empty()
-                .unwrap();
+            // With irregular masks the field might be on an island full of mines
+            let new_place = match new_place {
+                Some(place) => place,
+                None => self
+                    .find_first_field_without_mine()
+                    .ok_or(TOO_MUCH_MINES_ERROR)?,
+            };
+
             self.mine_locations.remove(&(row, col));
             self.mine_locations.insert(new_place);
-            let mut fields_to_recalculate = HashSet::new();
-            fields_to_recalculate.extend(self.get_neighbor_fields(row, col).into_iter());
-            fields_to_recalculate.extend(
-                self.get_neighbor_fields(new_place.0, new_place.1)
-                    .into_iter(),
-            );
-            fields_to_recalculate.insert((row, col));
-            for (r, c) in fields_to_recalculate {
-                if !self.get_field_unchecked(r, c).get_field_type().is_mine() {
-                    let field_value = self.get_field_value(r, c).unwrap();
-                    match field_value {
-                        0 => self
-                            .get_field_unchecked_mut(r, c)
-                            .update_type_to_empty()
-                            .unwrap(),
-                        _ => self
-                            .get_field_unchecked_mut(r, c)
-                            .update_type_with_value(field_value)
-                            .unwrap(),
-                    };
-                }
-            }
+            self.recalculate_field_types_around(&[(row, col), new_place])?;
         }
 
         Ok(())
     }
 
+    fn find_first_field_without_mine(&self) -> Option<(SizeType, SizeType)> {
+        for row in 0..self.mask.height() {
+            for column in 0..self.mask.width() {
+                if self.mask.is_present(row, column)
+                    && !self.mine_locations.contains(&(row, column))
+                {
+                    return Some((row, column));
+                }
+            }
+        }
+        None
+    }
+
     fn construct_boom_result(&self) -> OpenInfo {
         let mut boom_result = OpenInfo {
             result: OpenResult::Boom,
             newly_opened_fields: HashMap::new(),
         };
-        for row in 0..self.height {
-            for column in 0..self.width {
+        for row in 0..self.mask.height() {
+            for column in 0..self.mask.width() {
+                if !self.mask.is_present(row, column) {
+                    continue;
+                }
                 boom_result.newly_opened_fields.insert(
                     (row, column),
                     self.get_field_unchecked(row, column).get_field_type(),
@@ -474,11 +592,14 @@ impl BasicTable {
         while let Some((r, c)) = visiter.next() {
             match self.get_field_unchecked_mut(r, c).open() {
                 FieldOpenResult::MultiOpen => {
-                    self.number_of_opened_fields = self.number_of_opened_fields + 1;
-                    visiter.extend_with_unvisited_neighbors(r, c);
+                    self.number_of_opened_fields += 1;
+                    // Depending on the clue rule an empty field might have mines around it
+                    if !self.has_mine_in_clue_fields(r, c) {
+                        visiter.extend_with_unvisited_neighbors(r, c);
+                    }
                 }
                 FieldOpenResult::SimpleOpen => {
-                    self.number_of_opened_fields = self.number_of_opened_fields + 1;
+                    self.number_of_opened_fields += 1;
                 }
                 FieldOpenResult::Boom => has_boomed = true,
                 _ => continue,
@@ -507,39 +628,64 @@ impl BasicTable {
         }
     }
 
-    fn count_flagged_neighbors(&self, row: SizeType, col: SizeType) -> Result<u8, &'static str> {
-        let mut visiter = FieldVisiter::new(self.height, self.width, row, col)?;
-
-        visiter.extend_with_unvisited_neighbors(row, col);
-
-        let mut number_of_flagged_neighbors: u8 = 0;
-        while let Some((r, c)) = visiter.next() {
-            if row == r && col == c {
-                continue;
-            }
-            if self.get_field_unchecked(r, c).get_field_state() == FieldState::Flagged {
-                number_of_flagged_neighbors += 1;
-            }
-        }
-        Ok(number_of_flagged_neighbors)
+    // The value of the flags among the chord fields, evaluated like the value of the mines
+    fn get_flagged_value(
+        &self,
+        row: SizeType,
+        col: SizeType,
+        chord_fields: &HashSet<(SizeType, SizeType)>,
+    ) -> u8 {
+        let flagged_fields = chord_fields
+            .iter()
+            .filter(|&&(r, c)| {
+                self.get_field_unchecked(r, c)
+                    .get_field_state()
+                    .is_flagged()
+            })
+            .cloned()
+            .collect();
+        let random_number = self.random_numbers[row as usize][col as usize];
+        self.clue_rule
+            .get_value(row, col, &flagged_fields, random_number)
     }
 
     fn validate_indices(&self, row: SizeType, col: SizeType) -> Result<(), &'static str> {
-        if row < 0 || row >= self.height || col < 0 || col >= self.width {
+        if !self.mask.is_inside(row, col) {
             Err(INVALID_INDEX_ERROR)
         } else {
             Ok(())
         }
     }
+
+    fn validate_field_exists(&self, row: SizeType, col: SizeType) -> Result<(), &'static str> {
+        self.validate_indices(row, col)?;
+        if !self.mask.is_present(row, col) {
+            Err(VOID_FIELD_ERROR)
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl Table for BasicTable {
+    fn clone_box(&self) -> Box<dyn Table> {
+        Box::new(self.clone())
+    }
+
     fn width(&self) -> SizeType {
-        self.width
+        self.mask.width()
     }
 
     fn height(&self) -> SizeType {
-        self.height
+        self.mask.height()
+    }
+
+    fn number_of_mines(&self) -> SizeType {
+        self.mine_locations.len() as SizeType
+    }
+
+    fn max_number_of_newly_opened_fields(&self) -> usize {
+        self.mask.number_of_present_fields() as usize
     }
 
     fn get_field_info(&self, row: SizeType, col: SizeType) -> Result<FieldInfo, &'static str> {
@@ -548,7 +694,7 @@ impl Table for BasicTable {
     }
 
     fn open_field(&mut self, row: SizeType, col: SizeType) -> Result<OpenInfo, &'static str> {
-        self.validate_indices(row, col)?;
+        self.validate_field_exists(row, col)?;
 
         if self
             .get_field_unchecked(row, col)
@@ -570,12 +716,12 @@ impl Table for BasicTable {
             self.move_mine(row, col)?;
         }
 
-        let mut visiter = FieldVisiter::new(self.height, self.width, row, col)?;
+        let mut visiter = self.create_visiter(row, col)?;
         self.execute_open(&mut visiter)
     }
 
     fn open_neighbors(&mut self, row: SizeType, col: SizeType) -> Result<OpenInfo, &'static str> {
-        self.validate_indices(row, col)?;
+        self.validate_field_exists(row, col)?;
 
         let empty_open_info = OpenInfo {
             result: OpenResult::Ok,
@@ -590,10 +736,15 @@ impl Table for BasicTable {
             return Ok(empty_open_info);
         }
 
+        let chord_fields = match self.clue_rule.get_chord_fields(&self.mask, row, col) {
+            Some(chord_fields) => chord_fields,
+            None => return Ok(empty_open_info),
+        };
+
         match self.get_field_unchecked(row, col).get_field_type() {
-            FieldType::Numbered(x) if x == self.count_flagged_neighbors(row, col)? => {
-                let mut visiter = FieldVisiter::new(self.height, self.width, row, col)?;
-                visiter.extend_with_unvisited_neighbors(row, col);
+            FieldType::Numbered(x) if x == self.get_flagged_value(row, col, &chord_fields) => {
+                let mut visiter = self.create_visiter(row, col)?;
+                visiter.extend_with_unvisited_fields(&chord_fields);
                 self.execute_open(&mut visiter)
             }
             _ => Ok(empty_open_info),
@@ -601,7 +752,7 @@ impl Table for BasicTable {
     }
 
     fn toggle_flag(&mut self, row: SizeType, col: SizeType) -> Result<FlagResult, &'static str> {
-        self.validate_indices(row, col)?;
+        self.validate_field_exists(row, col)?;
 
         Ok(self.get_field_unchecked_mut(row, col).toggle_flag())
     }
@@ -609,6 +760,8 @@ impl Table for BasicTable {
 
 #[cfg(test)]
 mod test {
+    use super::super::clue_rule::{CrossClueRule, LiarClueRule, MineColor, PartialClueRule};
+    use super::super::mask::TOO_MUCH_FIELDS;
     use super::*;
     use lazy_static::lazy_static;
     use std::cell::RefCell;
@@ -713,8 +866,8 @@ mod test {
     fn check_open_info(
         open_info: &OpenInfo,
         expected_result: &OpenResult,
-        expected_fields: &Vec<(SizeType, SizeType)>,
-        test_fields: &Vec<Vec<FieldType>>,
+        expected_fields: &[(SizeType, SizeType)],
+        test_fields: &[Vec<FieldType>],
     ) {
         assert_eq!(open_info.result, *expected_result);
         assert_eq!(open_info.newly_opened_fields.len(), expected_fields.len());
@@ -808,7 +961,14 @@ mod test {
     fn get_value_of_mine() {
         let mut mine_locations = HashSet::new();
         mine_locations.insert((1, 1));
-        let result = get_field_value(10, 10, 1, 1, &mine_locations);
+        let result = get_field_value(
+            &Mask::new(10, 10).unwrap(),
+            &StandardClueRule,
+            1,
+            1,
+            &mine_locations,
+            0,
+        );
         assert!(result.is_err());
         assert_eq!(MINE_DOES_NOT_HAVE_VALUE_ERROR, result.err().unwrap());
     }
@@ -863,10 +1023,11 @@ mod test {
         expected_values.insert((5, 2, 0));
         expected_values.insert((5, 3, 0));
         expected_values.insert((5, 4, 2));
+        let mask = Mask::new(height, width).unwrap();
         for (row, col, expected_value) in &expected_values {
             assert_eq!(
                 *expected_value,
-                get_field_value(height, width, *row, *col, &mine_locations).unwrap()
+                get_field_value(&mask, &StandardClueRule, *row, *col, &mine_locations, 0).unwrap()
             )
         }
     }
@@ -890,12 +1051,30 @@ mod test {
         ));
     }
 
+    #[test]
+    fn same_seed_same_mine_locations() {
+        let table = BasicTable::with_seed(16, 30, 99, 42).unwrap();
+        assert_eq!(99, table.mine_locations.len());
+        assert_eq!(
+            table.mine_locations,
+            BasicTable::with_seed(16, 30, 99, 42)
+                .unwrap()
+                .mine_locations
+        );
+        assert_ne!(
+            table.mine_locations,
+            BasicTable::with_seed(16, 30, 99, 43)
+                .unwrap()
+                .mine_locations
+        );
+    }
+
     #[test]
     fn field_visiter() {
         let table = BasicTable::new(10, 15, 10).unwrap();
         let base_row = 5;
         let base_col = 10;
-        let mut visiter = FieldVisiter::new(table.height, table.width, base_row, base_col).unwrap();
+        let mut visiter = table.create_visiter(base_row, base_col).unwrap();
         let mut expected_fields_to_visit = IndexSet::new();
         expected_fields_to_visit.insert((base_row, base_col));
         assert_eq!(expected_fields_to_visit, visiter.fields_to_visit);
@@ -1313,7 +1492,7 @@ mod test {
     fn with_custom_mines_invalid_mine_locations() {
         const HEIGHT: SizeType = 4;
         const WIDTH: SizeType = 6;
-        let expected_error: Result<BasicTable, &'static str> = Err(INVALID_MINE_LOCATIONS);
+        let expected_error = Some(INVALID_MINE_LOCATIONS);
         {
             let mut mine_locations = HashSet::new();
             mine_locations.insert((HEIGHT - 1, WIDTH - 1));
@@ -1328,7 +1507,8 @@ mod test {
             mine_locations.insert((HEIGHT, WIDTH - 1));
             let result = BasicTable::with_custom_mines(HEIGHT, WIDTH, mine_locations);
             assert_eq!(
-                expected_error, result,
+                expected_error,
+                result.err(),
                 "Mine row is too high, but not detected"
             );
         }
@@ -1337,7 +1517,8 @@ mod test {
             mine_locations.insert((HEIGHT - 1, WIDTH));
             let result = BasicTable::with_custom_mines(HEIGHT, WIDTH, mine_locations);
             assert_eq!(
-                expected_error, result,
+                expected_error,
+                result.err(),
                 "Mine col is too high, but not detected"
             );
         }
@@ -1346,7 +1527,8 @@ mod test {
             mine_locations.insert((HEIGHT, WIDTH));
             let result = BasicTable::with_custom_mines(HEIGHT, WIDTH, mine_locations);
             assert_eq!(
-                expected_error, result,
+                expected_error,
+                result.err(),
                 "Mine row and col are too high, but not detected"
             );
         }
@@ -1434,7 +1616,7 @@ mod test {
                 let field_info = table.get_field_info(row, col).unwrap();
                 let expected_field_info = FieldInfo {
                     state: FieldState::Opened,
-                    field_type: test_info.fields[row as usize][col as usize].clone(),
+                    field_type: test_info.fields[row as usize][col as usize],
                 };
                 assert_eq!(expected_field_info, field_info);
             }
@@ -1457,7 +1639,7 @@ mod test {
                 if row + col <= 3 && row < 3 && col < 3 {
                     let expected_field_info = FieldInfo {
                         state: FieldState::Opened,
-                        field_type: test_info.fields[row as usize][col as usize].clone(),
+                        field_type: test_info.fields[row as usize][col as usize],
                     };
                     assert_eq!(expected_field_info, field_info);
                 } else {
@@ -1475,7 +1657,7 @@ mod test {
                 if row + col <= 3 {
                     let expected_field_info = FieldInfo {
                         state: FieldState::Opened,
-                        field_type: test_info.fields[row as usize][col as usize].clone(),
+                        field_type: test_info.fields[row as usize][col as usize],
                     };
                     assert_eq!(expected_field_info, field_info);
                 } else {
@@ -1485,5 +1667,300 @@ mod test {
         }
     }
 
+    fn create_donut_table(mine_locations: &[(SizeType, SizeType)]) -> BasicTable {
+        let mask = Mask::from_rows(&["###", "#.#", "###"]).unwrap();
+        BasicTable::with_mask_and_custom_mines(
+            mask,
+            Arc::new(StandardClueRule),
+            mine_locations.iter().cloned().collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    //     0 1 2
+    //     - - -
+    // 0 | M 1 0
+    // 1 | 1 V 0
+    // 2 | 0 0 0
+    fn masked_field_values() {
+        let table = create_donut_table(&[(0, 0)]);
+        assert_eq!(
+            FieldType::Numbered(1),
+            table.get_field_unchecked(0, 1).get_field_type()
+        );
+        assert_eq!(
+            FieldType::Numbered(1),
+            table.get_field_unchecked(1, 0).get_field_type()
+        );
+        for &(row, col) in &[(0, 2), (1, 2), (2, 0), (2, 1), (2, 2)] {
+            assert_eq!(
+                FieldType::Empty,
+                table.get_field_unchecked(row, col).get_field_type()
+            );
+        }
+        assert!(!table.get_clue_fields(0, 1).contains(&(1, 1)));
+    }
+
+    #[test]
+    fn void_field() {
+        let mut table = create_donut_table(&[(0, 0)]);
+        let expected_field_info = FieldInfo {
+            state: FieldState::Void,
+            field_type: FieldType::Empty,
+        };
+        assert_eq!(Ok(expected_field_info), table.get_field_info(1, 1));
+        assert_eq!(Err(VOID_FIELD_ERROR), table.open_field(1, 1));
+        assert_eq!(Err(VOID_FIELD_ERROR), table.open_neighbors(1, 1));
+        assert_eq!(Err(VOID_FIELD_ERROR), table.toggle_flag(1, 1));
+    }
+
+    #[test]
+    fn masked_win() {
+        let mut table = create_donut_table(&[(0, 0)]);
+        let open_info = table.open_field(2, 2).unwrap();
+        assert_eq!(OpenResult::WINNER, open_info.result);
+        assert_eq!(8, open_info.newly_opened_fields.len());
+        assert_eq!(None, open_info.newly_opened_fields.get(&(1, 1)));
+        assert_eq!(
+            Some(&FieldType::Mine),
+            open_info.newly_opened_fields.get(&(0, 0))
+        );
+    }
+
+    #[test]
+    fn masked_boom_result() {
+        let mut table = create_donut_table(&[(0, 0), (2, 2)]);
+        let open_info = table.open_field(0, 2).unwrap();
+        assert_eq!(OpenResult::Ok, open_info.result);
+        assert_eq!(3, open_info.newly_opened_fields.len());
+
+        let boom_info = table.open_field(0, 0).unwrap();
+        assert_eq!(OpenResult::Boom, boom_info.result);
+        assert_eq!(8, boom_info.newly_opened_fields.len());
+        assert_eq!(None, boom_info.newly_opened_fields.get(&(1, 1)));
+    }
+
+    #[test]
+    fn first_open_on_island_moves_mine_away() {
+        let mask = Mask::from_rows(&["#.#"]).unwrap();
+        let mine_locations = [(0, 0)].iter().cloned().collect();
+        let mut table = BasicTable::with_mask_and_custom_mines(
+            mask,
+            Arc::new(StandardClueRule),
+            mine_locations,
+        )
+        .unwrap();
+        let open_info = table.open_field(0, 0).unwrap();
+        assert_eq!(OpenResult::WINNER, open_info.result);
+        assert_eq!(
+            Some(&FieldType::Mine),
+            open_info.newly_opened_fields.get(&(0, 2))
+        );
+    }
+
+    #[test]
+    fn masked_mine_locations() {
+        let mask = Mask::from_rows(&["###", "#.#", "###"]).unwrap();
+        let mine_locations = [(1, 1)].iter().cloned().collect();
+        assert_eq!(
+            Some(INVALID_MINE_LOCATIONS),
+            BasicTable::with_mask_and_custom_mines(
+                mask.clone(),
+                Arc::new(StandardClueRule),
+                mine_locations
+            )
+            .err()
+        );
+        assert_eq!(
+            Some(TOO_MUCH_MINES_ERROR),
+            BasicTable::with_mask(mask.clone(), 8).err()
+        );
+        let table = BasicTable::with_mask(mask, 7).unwrap();
+        assert!(!table.mine_locations.contains(&(1, 1)));
+        assert_eq!(7, table.mine_locations.len());
+    }
+
+    fn create_table_with_clue_rule(
+        rows: &[&str],
+        clue_rule: Arc<dyn ClueRule>,
+        mine_locations: &[(SizeType, SizeType)],
+    ) -> BasicTable {
+        BasicTable::with_mask_and_custom_mines(
+            Mask::from_rows(rows).unwrap(),
+            clue_rule,
+            mine_locations.iter().cloned().collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    //     0 1 2 3 4
+    //     - - - - -
+    // 0 | M 1 1 0 0
+    fn cross_clue_rule_open() {
+        let mut table = create_table_with_clue_rule(&["#####"], Arc::new(CrossClueRule), &[(0, 0)]);
+        assert_eq!(
+            FieldType::Numbered(1),
+            table.get_field_unchecked(0, 2).get_field_type()
+        );
+        let open_info = table.open_field(0, 4).unwrap();
+        assert_eq!(OpenResult::WINNER, open_info.result);
+        assert_eq!(
+            Some(&FieldType::Numbered(1)),
+            open_info.newly_opened_fields.get(&(0, 1))
+        );
+    }
+
+    #[test]
+    fn cross_clue_rule_open_neighbors() {
+        let mut table = create_table_with_clue_rule(&["#####"], Arc::new(CrossClueRule), &[(0, 0)]);
+        assert_eq!(OpenResult::Ok, table.open_field(0, 2).unwrap().result);
+        assert_eq!(FlagResult::Flagged, table.toggle_flag(0, 0).unwrap());
+        let open_info = table.open_neighbors(0, 2).unwrap();
+        assert_eq!(OpenResult::WINNER, open_info.result);
+        for col in &[1, 3, 4] {
+            assert!(open_info.newly_opened_fields.contains_key(&(0, *col)));
+        }
+    }
+
+    #[test]
+    //     0 1 2 3
+    //     - - - -
+    // 0 | M 0 0 0
+    fn empty_field_with_uncounted_mine_is_not_cascaded() {
+        let clue_rule = Arc::new(PartialClueRule {
+            counted_color: MineColor::Dark,
+        });
+        let mut table = create_table_with_clue_rule(&["####"], clue_rule, &[(0, 0)]);
+        assert_eq!(
+            FieldType::Empty,
+            table.get_field_unchecked(0, 1).get_field_type()
+        );
+        let open_info = table.open_field(0, 3).unwrap();
+        assert_eq!(OpenResult::WINNER, open_info.result);
+        assert_eq!(4, open_info.newly_opened_fields.len());
+    }
+
+    #[test]
+    fn liar_clue_rule_never_cascades_into_mine() {
+        for _ in 0..20 {
+            let mut table =
+                create_table_with_clue_rule(&["#####"], Arc::new(LiarClueRule), &[(0, 0)]);
+            let open_info = table.open_field(0, 4).unwrap();
+            assert_ne!(OpenResult::Boom, open_info.result);
+            for ((row, col), field_type) in &open_info.newly_opened_fields {
+                if *field_type != FieldType::Mine {
+                    let value = match field_type {
+                        FieldType::Numbered(value) => *value,
+                        _ => 0,
+                    };
+                    let real_value = if (*row, *col) == (0, 1) { 1 } else { 0 };
+                    assert_eq!(1, (value as i8 - real_value as i8).abs());
+                }
+            }
+        }
+    }
+
+    #[test]
+    //     0 1 2 3 4 5
+    //     - - - - - -
+    // 0 | M 1 0 0 1 M
+    fn moved_mine_keeps_the_liar_clues_of_other_fields() {
+        for _ in 0..20 {
+            let mut table =
+                create_table_with_clue_rule(&["######"], Arc::new(LiarClueRule), &[(0, 0), (0, 5)]);
+            let field_type = table.get_field_unchecked(0, 4).get_field_type();
+            // The mine is moved to (0, 1), so only the clues of (0, 0) and (0, 2) change
+            table.open_field(0, 0).unwrap();
+            assert!(table.mine_locations.contains(&(0, 1)));
+            assert_eq!(field_type, table.get_field_unchecked(0, 4).get_field_type());
+            assert_eq!(
+                FieldType::Numbered(1),
+                table.get_field_unchecked(0, 3).get_field_type()
+            );
+        }
+    }
+
+    #[test]
+    fn same_seed_same_liar_clues() {
+        let create_table = |seed| {
+            BasicTable::with_mask_clue_rule_and_seed(
+                Mask::new(16, 30).unwrap(),
+                99,
+                Arc::new(LiarClueRule),
+                seed,
+            )
+            .unwrap()
+        };
+        let field_types = |table: &BasicTable| -> Vec<FieldType> {
+            table
+                .fields
+                .iter()
+                .flatten()
+                .map(|field| field.get_field_type())
+                .collect()
+        };
+        let table = create_table(42);
+        assert_eq!(field_types(&table), field_types(&create_table(42)));
+        assert_ne!(field_types(&table), field_types(&create_table(43)));
+        assert_eq!(
+            table.mine_locations,
+            BasicTable::with_seed(16, 30, 99, 42)
+                .unwrap()
+                .mine_locations
+        );
+    }
+
+    fn create_partial_table() -> BasicTable {
+        let clue_rule = Arc::new(PartialClueRule {
+            counted_color: MineColor::Light,
+        });
+        create_table_with_clue_rule(&["###", "###"], clue_rule, &[(0, 0), (0, 1)])
+    }
+
+    #[test]
+    //     0 1 2
+    //     - - -
+    // 0 | M M 0
+    // 1 | 1 1 0
+    fn partial_clue_rule_open_neighbors() {
+        // Only the flag of the light mine is counted and only the light fields are opened
+        for flags in &[&[(0, 0)][..], &[(0, 0), (0, 1)]] {
+            let mut table = create_partial_table();
+            assert_eq!(OpenResult::Ok, table.open_field(1, 1).unwrap().result);
+            for &(row, col) in flags.iter() {
+                assert_eq!(FlagResult::Flagged, table.toggle_flag(row, col).unwrap());
+            }
+            let open_info = table.open_neighbors(1, 1).unwrap();
+            assert_eq!(OpenResult::Ok, open_info.result);
+            assert_eq!(1, open_info.newly_opened_fields.len());
+            assert_eq!(
+                Some(&FieldType::Empty),
+                open_info.newly_opened_fields.get(&(0, 2))
+            );
+        }
+    }
+
+    #[test]
+    fn partial_clue_rule_open_neighbors_with_uncounted_flag() {
+        let mut table = create_partial_table();
+        assert_eq!(OpenResult::Ok, table.open_field(1, 1).unwrap().result);
+        assert_eq!(FlagResult::Flagged, table.toggle_flag(0, 1).unwrap());
+        let open_info = table.open_neighbors(1, 1).unwrap();
+        assert_eq!(OpenResult::Ok, open_info.result);
+        assert!(open_info.newly_opened_fields.is_empty());
+    }
+
+    #[test]
+    fn liar_clue_rule_open_neighbors() {
+        let mut table = create_table_with_clue_rule(&["###"], Arc::new(LiarClueRule), &[(0, 0)]);
+        assert_eq!(OpenResult::Ok, table.open_field(0, 1).unwrap().result);
+        assert_eq!(FlagResult::Flagged, table.toggle_flag(0, 0).unwrap());
+        let open_info = table.open_neighbors(0, 1).unwrap();
+        assert_eq!(OpenResult::Ok, open_info.result);
+        assert!(open_info.newly_opened_fields.is_empty());
+    }
+
     // TODO Write test to full game
 }
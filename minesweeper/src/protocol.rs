@@ -0,0 +1,323 @@
+// Line based protocol for driving games from other programs. Every command is a single line and
+// every response is a single line JSON object. The successful responses contain "ok": true, the
+// failed ones contain "ok": false and an "error" message.
+//
+// Commands:
+//  - new <beginner|intermediate|expert> [seed=<seed>]
+//  - new custom <height> <width> <number of mines> [seed=<seed>]
+//  - open <row> <column>
+//  - flag <row> <column>
+//  - chord <row> <column>
+//  - board
+//  - status
+//  - quit
+//
+// The fields of the board are represented by numbers: 0-8 are the opened fields, -1 is a closed
+// field, -2 is a flagged field, -3 is an opened mine and -4 is a field that is not part of the
+// board.
+use minesweeper::{Game, GameLevel, GameState, OpenInfo, SizeType};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+static NO_GAME_ERROR: &str = "There is no game, please start a new one!";
+static UNKNOWN_COMMAND_ERROR: &str = "Unknown command!";
+static INVALID_NUMBER_OF_ARGUMENTS_ERROR: &str = "Invalid number of arguments!";
+static INVALID_LEVEL_ERROR: &str = "Invalid level!";
+static INVALID_NUMBER_ERROR: &str = "Invalid number!";
+static INVALID_OPTION_ERROR: &str = "Invalid option!";
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> Result<T, &'static str> {
+    text.parse::<T>().map_err(|_| INVALID_NUMBER_ERROR)
+}
+
+fn parse_coords(arguments: &[&str]) -> Result<(SizeType, SizeType), &'static str> {
+    match arguments {
+        [row, col] => Ok((parse_number(row)?, parse_number(col)?)),
+        _ => Err(INVALID_NUMBER_OF_ARGUMENTS_ERROR),
+    }
+}
+
+fn create_open_response(open_info: OpenInfo) -> Value {
+    let mut opened_fields: Vec<Value> = open_info
+        .newly_opened_fields
+        .into_iter()
+        .map(|((row, col), field_type)| json!([row, col, field_type.to_value()]))
+        .collect();
+    // The order of the hash map is not deterministic
+    opened_fields.sort_by_key(|field| (field[0].as_i64(), field[1].as_i64()));
+    json!({
+        "ok": true,
+        "result": open_info.result.to_string(),
+        "opened_fields": opened_fields,
+    })
+}
+
+#[derive(Default)]
+pub struct Session {
+    game: Option<Game>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    fn get_game(&mut self) -> Result<&mut Game, &'static str> {
+        self.game.as_mut().ok_or(NO_GAME_ERROR)
+    }
+
+    fn new_game(&mut self, arguments: &[&str]) -> Result<Value, &'static str> {
+        let mut seed = None;
+        let mut positional_arguments = Vec::new();
+        for argument in arguments {
+            match argument.strip_prefix("seed=") {
+                Some(value) => seed = Some(parse_number::<u64>(value)?),
+                None if argument.contains('=') => return Err(INVALID_OPTION_ERROR),
+                None => positional_arguments.push(*argument),
+            }
+        }
+
+        let level = match positional_arguments.first() {
+            Some(&"beginner") => Some(GameLevel::Beginner),
+            Some(&"intermediate") => Some(GameLevel::Intermediate),
+            Some(&"expert") => Some(GameLevel::Expert),
+            Some(&"custom") => None,
+            Some(_) => return Err(INVALID_LEVEL_ERROR),
+            None => return Err(INVALID_NUMBER_OF_ARGUMENTS_ERROR),
+        };
+        let game = match (level, &positional_arguments[1..]) {
+            (Some(level), []) => match seed {
+                Some(seed) => Game::new_with_seed(level, seed),
+                None => Game::new(level),
+            },
+            (None, [height, width, number_of_mines]) => {
+                let (height, width, number_of_mines) = (
+                    parse_number(height)?,
+                    parse_number(width)?,
+                    parse_number(number_of_mines)?,
+                );
+                match seed {
+                    Some(seed) => Game::new_custom_with_seed(height, width, number_of_mines, seed)?,
+                    None => Game::new_custom(height, width, number_of_mines)?,
+                }
+            }
+            _ => return Err(INVALID_NUMBER_OF_ARGUMENTS_ERROR),
+        };
+        let response = json!({
+            "ok": true,
+            "height": game.height(),
+            "width": game.width(),
+            "mines": game.number_of_mines(),
+        });
+        self.game = Some(game);
+        Ok(response)
+    }
+
+    fn board(&mut self) -> Result<Value, &'static str> {
+        let game = self.get_game()?;
+        let mut rows = Vec::new();
+        for row in 0..game.height() {
+            let mut values = Vec::new();
+            for col in 0..game.width() {
+                values.push(game.get_field_info(row, col)?.to_value());
+            }
+            rows.push(values);
+        }
+        Ok(json!({ "ok": true, "board": rows }))
+    }
+
+    fn status(&mut self) -> Result<Value, &'static str> {
+        let game = self.get_game()?;
+        let (state, loss_reason) = match game.get_state() {
+            GameState::Lost(reason) => ("Lost".to_string(), Some(reason.to_string())),
+            state => (state.to_string(), None),
+        };
+        Ok(json!({
+            "ok": true,
+            "state": state,
+            "loss_reason": loss_reason,
+            "elapsed_ms": game.get_elapsed().as_millis() as u64,
+            "height": game.height(),
+            "width": game.width(),
+            "mines": game.number_of_mines(),
+        }))
+    }
+
+    fn execute_command(&mut self, line: &str) -> Result<Value, &'static str> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (command, arguments) = match words.split_first() {
+            Some((command, arguments)) => (*command, arguments),
+            None => return Err(UNKNOWN_COMMAND_ERROR),
+        };
+        match command {
+            "new" => self.new_game(arguments),
+            "open" => {
+                let (row, col) = parse_coords(arguments)?;
+                Ok(create_open_response(self.get_game()?.open(row, col)?))
+            }
+            "chord" => {
+                let (row, col) = parse_coords(arguments)?;
+                Ok(create_open_response(
+                    self.get_game()?.open_neighbors(row, col)?,
+                ))
+            }
+            "flag" => {
+                let (row, col) = parse_coords(arguments)?;
+                let flag_result = self.get_game()?.toggle_flag(row, col)?;
+                Ok(json!({ "ok": true, "result": flag_result.to_string() }))
+            }
+            "board" if arguments.is_empty() => self.board(),
+            "status" if arguments.is_empty() => self.status(),
+            "board" | "status" => Err(INVALID_NUMBER_OF_ARGUMENTS_ERROR),
+            _ => Err(UNKNOWN_COMMAND_ERROR),
+        }
+    }
+
+    pub fn execute(&mut self, line: &str) -> Value {
+        self.execute_command(line)
+            .unwrap_or_else(|error| json!({ "ok": false, "error": error }))
+    }
+}
+
+pub fn run(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let mut session = Session::new();
+    for line in input.lines() {
+        let line = line?;
+        let command = line.trim();
+        if command == "quit" {
+            break;
+        }
+        if command.is_empty() {
+            continue;
+        }
+        writeln!(output, "{}", session.execute(command))?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check_error(response: Value, error: &str) {
+        assert_eq!(json!({ "ok": false, "error": error }), response);
+    }
+
+    #[test]
+    fn commands_without_game() {
+        let mut session = Session::new();
+        check_error(session.execute("open 1 1"), NO_GAME_ERROR);
+        check_error(session.execute("board"), NO_GAME_ERROR);
+        check_error(session.execute("status"), NO_GAME_ERROR);
+    }
+
+    #[test]
+    fn invalid_commands() {
+        let mut session = Session::new();
+        check_error(session.execute("dance"), UNKNOWN_COMMAND_ERROR);
+        check_error(session.execute("new"), INVALID_NUMBER_OF_ARGUMENTS_ERROR);
+        check_error(session.execute("new hard"), INVALID_LEVEL_ERROR);
+        check_error(
+            session.execute("new expert 3"),
+            INVALID_NUMBER_OF_ARGUMENTS_ERROR,
+        );
+        check_error(session.execute("new expert speed=3"), INVALID_OPTION_ERROR);
+        check_error(session.execute("new expert seed=x"), INVALID_NUMBER_ERROR);
+        check_error(
+            session.execute("new custom 3 3"),
+            INVALID_NUMBER_OF_ARGUMENTS_ERROR,
+        );
+        check_error(
+            session.execute("new custom 3 3 9"),
+            minesweeper::TOO_MUCH_MINES_ERROR,
+        );
+        session.execute("new beginner");
+        check_error(session.execute("open 1"), INVALID_NUMBER_OF_ARGUMENTS_ERROR);
+        check_error(session.execute("flag 1 a"), INVALID_NUMBER_ERROR);
+        check_error(
+            session.execute("board 1"),
+            INVALID_NUMBER_OF_ARGUMENTS_ERROR,
+        );
+    }
+
+    #[test]
+    fn new_game() {
+        let mut session = Session::new();
+        assert_eq!(
+            json!({ "ok": true, "height": 16, "width": 30, "mines": 99 }),
+            session.execute("new expert seed=42")
+        );
+        assert_eq!(
+            json!({ "ok": true, "height": 4, "width": 5, "mines": 3 }),
+            session.execute("new custom 4 5 3")
+        );
+    }
+
+    #[test]
+    fn same_seed_same_responses() {
+        let commands = ["new intermediate seed=7", "open 8 8", "chord 8 8", "board"];
+        let mut session = Session::new();
+        let mut other_session = Session::new();
+        for command in commands.iter() {
+            assert_eq!(session.execute(command), other_session.execute(command));
+        }
+    }
+
+    #[test]
+    fn play() {
+        let mut session = Session::new();
+        session.execute("new custom 3 3 8 seed=3");
+        assert_eq!(
+            json!({ "ok": true, "result": "Flagged" }),
+            session.execute("flag 0 0")
+        );
+
+        let status = session.execute("status");
+        assert_eq!(json!("Started"), status["state"]);
+        assert_eq!(Value::Null, status["loss_reason"]);
+
+        // The opened fields are sorted and the mines are revealed at the end of the game
+        let mut opened_fields = vec![json!([1, 1, 8])];
+        for (row, col) in [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (1, 0),
+            (1, 2),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+        ]
+        .iter()
+        {
+            opened_fields.push(json!([row, col, minesweeper::MINE_VALUE]));
+        }
+        opened_fields.sort_by_key(|field| (field[0].as_i64(), field[1].as_i64()));
+        assert_eq!(
+            json!({ "ok": true, "result": "WINNER", "opened_fields": opened_fields }),
+            session.execute("open 1 1")
+        );
+
+        let board = session.execute("board");
+        assert_eq!(json!(true), board["ok"]);
+        assert_eq!(3, board["board"].as_array().unwrap().len());
+        assert_eq!(json!(minesweeper::FLAGGED_VALUE), board["board"][0][0]);
+        assert_eq!(json!(8), board["board"][1][1]);
+        assert_eq!(json!("Won"), session.execute("status")["state"]);
+    }
+
+    #[test]
+    fn run_until_quit() {
+        let input = "new beginner\n\nstatus\nquit\nstatus\n";
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output).unwrap();
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(2, responses.len());
+        assert_eq!(json!("NotStarted"), responses[1]["state"]);
+    }
+}
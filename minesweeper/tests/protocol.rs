@@ -0,0 +1,32 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn drive_demo_with_protocol() {
+    let mut demo = Command::new(env!("CARGO_BIN_EXE_minesweeper_demo"))
+        .arg("--protocol")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Unable to start the demo!");
+    demo.stdin
+        .take()
+        .unwrap()
+        .write_all(b"new custom 3 3 8 seed=1\nflag 0 0\nopen 1 1\nstatus\nquit\n")
+        .unwrap();
+    let output = demo.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<&str> = std::str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .collect();
+    assert_eq!(4, responses.len());
+    assert_eq!(
+        r#"{"height":3,"mines":8,"ok":true,"width":3}"#,
+        responses[0]
+    );
+    assert_eq!(r#"{"ok":true,"result":"Flagged"}"#, responses[1]);
+    assert!(responses[2].contains(r#""result":"WINNER""#));
+    assert!(responses[3].contains(r#""state":"Won""#));
+}
@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use monty_hall::{play_game, AlwaysSwitch, GameConfig, Simulation, StandardHost, Statistics};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::thread;
+
+const GAME_COUNT: u64 = 1 << 18;
+
+// Compares the old way of simulating, which creates a new game every time, with the runner
+fn simulation_benchmark(c: &mut Criterion) {
+    let config = GameConfig::classic(10).unwrap();
+    let mut group = c.benchmark_group("simulation");
+    group.throughput(Throughput::Elements(GAME_COUNT));
+    group.sample_size(10);
+
+    group.bench_function("allocating_games", |b| {
+        b.iter(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let mut statistics = Statistics::new();
+            for _ in 0..GAME_COUNT {
+                statistics.add(play_game(config, &StandardHost, &AlwaysSwitch, &mut rng).unwrap());
+            }
+            statistics
+        })
+    });
+
+    let mut simulation = Simulation::new(config, &StandardHost, &AlwaysSwitch, 0);
+    group.bench_function("reused_game", |b| {
+        b.iter(|| simulation.run(GAME_COUNT).unwrap())
+    });
+
+    let thread_count = thread::available_parallelism().map_or(1, |count| count.get());
+    simulation.set_thread_count(thread_count).unwrap();
+    group.bench_function(format!("reused_game_{}_threads", thread_count), |b| {
+        b.iter(|| simulation.run(GAME_COUNT).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, simulation_benchmark);
+criterion_main!(benches);
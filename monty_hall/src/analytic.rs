@@ -0,0 +1,132 @@
+// Exact win probabilities computed from closed forms with rational arithmetic
+use crate::config::GameConfig;
+use crate::host::Host;
+use crate::strategy::Strategy;
+use num_bigint::BigInt;
+use num_rational::{BigRational, Rational64};
+use num_traits::{One, ToPrimitive, Zero};
+
+pub fn ratio(numerator: usize, denominator: usize) -> BigRational {
+    BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+}
+
+// The probabilities given as floating point numbers are approximated by the simplest fraction, so
+// 0.1 is handled as 1/10
+pub fn probability_from_f64(probability: f64) -> Option<BigRational> {
+    let probability = Rational64::approximate_float(probability)?;
+    Some(BigRational::new(
+        BigInt::from(*probability.numer()),
+        BigInt::from(*probability.denom()),
+    ))
+}
+
+// The probability that the first choice of the contestant hides a prize
+pub fn first_choice_win_probability(config: &GameConfig) -> BigRational {
+    ratio(config.prize_count(), config.door_count())
+}
+
+// The probability of winning by switching to a random closed door after the host opened empty
+// doors only
+pub fn switch_win_probability(config: &GameConfig, first_choice_won: bool) -> BigRational {
+    let remaining_prize_count = config.prize_count() - first_choice_won as usize;
+    ratio(remaining_prize_count, config.switchable_door_count())
+}
+
+// The win probability of a game where the host offers a switch, the strategy switches with the
+// given probability and the host opens empty doors only
+pub fn offered_switch_win_probability(
+    config: &GameConfig,
+    first_choice_won: bool,
+    switch_probability: &BigRational,
+) -> BigRational {
+    let stay_win_probability = if first_choice_won {
+        BigRational::one()
+    } else {
+        BigRational::zero()
+    };
+    (BigRational::one() - switch_probability) * stay_win_probability
+        + switch_probability * switch_win_probability(config, first_choice_won)
+}
+
+// Only the built-in hosts and strategies are known, None is returned for the others
+pub fn exact_win_probability(
+    config: &GameConfig,
+    host: &dyn Host,
+    strategy: &dyn Strategy,
+) -> Option<BigRational> {
+    let switch_probability = probability_from_f64(strategy.switch_probability()?)?;
+    host.win_probability(config, &switch_probability)
+}
+
+pub fn win_probability(
+    config: &GameConfig,
+    host: &dyn Host,
+    strategy: &dyn Strategy,
+) -> Option<f64> {
+    exact_win_probability(config, host, strategy)?.to_f64()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::host::{AngelicHost, DevilishHost, MontyCrawl, MontyFall, StandardHost};
+    use crate::strategy::{AlwaysStay, AlwaysSwitch, RandomSwitch, SwitchWithProbability};
+
+    fn check_probability(
+        (numerator, denominator): (usize, usize),
+        config: &GameConfig,
+        host: &dyn Host,
+        strategy: &dyn Strategy,
+    ) {
+        assert_eq!(
+            Some(ratio(numerator, denominator)),
+            exact_win_probability(config, host, strategy)
+        );
+    }
+
+    #[test]
+    fn approximate_probabilities() {
+        assert_eq!(Some(ratio(1, 10)), probability_from_f64(0.1));
+        assert_eq!(Some(ratio(1, 2)), probability_from_f64(0.5));
+        assert_eq!(Some(ratio(0, 1)), probability_from_f64(0.0));
+        assert_eq!(None, probability_from_f64(f64::NAN));
+    }
+
+    #[test]
+    fn classic_game() {
+        let config = GameConfig::classic(3).unwrap();
+        for host in &[&StandardHost as &dyn Host, &MontyCrawl] {
+            check_probability((1, 3), &config, *host, &AlwaysStay);
+            check_probability((2, 3), &config, *host, &AlwaysSwitch);
+            check_probability((1, 2), &config, *host, &RandomSwitch);
+        }
+        check_probability((1, 3), &config, &MontyFall, &AlwaysSwitch);
+        check_probability((1, 3), &config, &AngelicHost, &AlwaysStay);
+        check_probability((1, 1), &config, &AngelicHost, &AlwaysSwitch);
+        check_probability((1, 3), &config, &DevilishHost, &AlwaysStay);
+        check_probability((0, 1), &config, &DevilishHost, &AlwaysSwitch);
+    }
+
+    #[test]
+    fn multiple_prizes_and_doors() {
+        let config = GameConfig::new(10, 3, 2).unwrap();
+        let strategy = SwitchWithProbability::new(0.25).unwrap();
+        // 3/4 * 2/10 + 1/4 * 2 * 9 / (10 * 6)
+        check_probability((9, 40), &config, &StandardHost, &strategy);
+        // 2/10 + 1/4 * 8/10 * 2/6
+        check_probability((4, 15), &config, &AngelicHost, &strategy);
+        // 2/10 * (3/4 + 1/4 * 1/6)
+        check_probability((19, 120), &config, &DevilishHost, &strategy);
+        check_probability((1, 5), &config, &MontyFall, &strategy);
+        assert_eq!(
+            Some(0.225),
+            win_probability(&config, &StandardHost, &strategy)
+        );
+    }
+
+    #[test]
+    fn many_doors() {
+        let config = GameConfig::classic(1_000_000).unwrap();
+        check_probability((999_999, 1_000_000), &config, &StandardHost, &AlwaysSwitch);
+    }
+}
@@ -0,0 +1,302 @@
+// Renders line charts of win rates as standalone SVG documents
+use std::fmt::Write;
+
+pub struct Point {
+    pub x: usize,
+    pub win_rate: f64,
+    // The 95% confidence interval, drawn as a vertical bar around the point
+    pub lower: f64,
+    pub upper: f64,
+}
+
+pub struct Series {
+    pub name: String,
+    pub points: Vec<Point>,
+}
+
+pub struct Panel {
+    pub title: String,
+    pub series: Vec<Series>,
+}
+
+const PANEL_WIDTH: f64 = 520.0;
+const PANEL_HEIGHT: f64 = 340.0;
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 120.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 50.0;
+const HEADER_HEIGHT: f64 = 40.0;
+const MAX_COLUMN_COUNT: usize = 3;
+const MAX_X_TICK_COUNT: usize = 10;
+const COLORS: [&str; 8] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+];
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn get_x_range(panels: &[Panel]) -> (usize, usize) {
+    let mut xs = panels
+        .iter()
+        .flat_map(|panel| panel.series.iter())
+        .flat_map(|series| series.points.iter())
+        .map(|point| point.x);
+    let first = xs.next().unwrap_or(0);
+    let (min, max) = xs.fold((first, first), |(min, max), x| (min.min(x), max.max(x)));
+    if min == max {
+        (min.saturating_sub(1), max + 1)
+    } else {
+        (min, max)
+    }
+}
+
+// Maps the values to the plot area of a panel whose top left corner is at (left, top)
+struct Scale {
+    left: f64,
+    top: f64,
+    min_x: usize,
+    max_x: usize,
+}
+
+impl Scale {
+    fn plot_width() -> f64 {
+        PANEL_WIDTH - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    fn plot_height() -> f64 {
+        PANEL_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM
+    }
+
+    fn x(&self, x: usize) -> f64 {
+        let ratio = (x - self.min_x) as f64 / (self.max_x - self.min_x) as f64;
+        self.left + MARGIN_LEFT + ratio * Scale::plot_width()
+    }
+
+    fn y(&self, win_rate: f64) -> f64 {
+        self.top + MARGIN_TOP + (1.0 - win_rate.clamp(0.0, 1.0)) * Scale::plot_height()
+    }
+}
+
+fn write_axes(svg: &mut String, scale: &Scale) {
+    let left = scale.x(scale.min_x);
+    let right = scale.x(scale.max_x);
+    let bottom = scale.y(0.0);
+    for tick in 0..=5 {
+        let win_rate = tick as f64 / 5.0;
+        let y = scale.y(win_rate);
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#dddddd"/>"##,
+            left, y, right, y
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end" dominant-baseline="middle">{}%</text>"#,
+            left - 6.0,
+            y,
+            tick * 20
+        );
+    }
+    let step = (scale.max_x - scale.min_x).div_ceil(MAX_X_TICK_COUNT);
+    for x in (scale.min_x..=scale.max_x).step_by(step) {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            scale.x(x),
+            bottom + 18.0,
+            x
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r##"<polyline points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" fill="none" stroke="#333333"/>"##,
+        left,
+        scale.y(1.0),
+        left,
+        bottom,
+        right,
+        bottom
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">number of doors</text>"#,
+        (left + right) / 2.0,
+        bottom + 38.0
+    );
+}
+
+fn write_series(svg: &mut String, scale: &Scale, series: &Series, index: usize) {
+    let color = COLORS[index % COLORS.len()];
+    let points: Vec<String> = series
+        .points
+        .iter()
+        .map(|point| format!("{:.1},{:.1}", scale.x(point.x), scale.y(point.win_rate)))
+        .collect();
+    let _ = writeln!(
+        svg,
+        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+        points.join(" "),
+        color
+    );
+    for point in &series.points {
+        let x = scale.x(point.x);
+        let _ = writeln!(
+            svg,
+            r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"/>"#,
+            x,
+            scale.y(point.lower),
+            x,
+            scale.y(point.upper),
+            color
+        );
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"/>"#,
+            x,
+            scale.y(point.win_rate),
+            color
+        );
+    }
+
+    let legend_x = scale.left + PANEL_WIDTH - MARGIN_RIGHT + 16.0;
+    let legend_y = scale.top + MARGIN_TOP + 8.0 + 20.0 * index as f64;
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"/>"#,
+        legend_x,
+        legend_y,
+        legend_x + 20.0,
+        legend_y,
+        color
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" dominant-baseline="middle">{}</text>"#,
+        legend_x + 26.0,
+        legend_y,
+        escape(&series.name)
+    );
+}
+
+// The panels are arranged in a grid and share the same axes
+pub fn render_svg(title: &str, panels: &[Panel]) -> String {
+    let (min_x, max_x) = get_x_range(panels);
+    let column_count = panels.len().clamp(1, MAX_COLUMN_COUNT);
+    let row_count = panels.len().div_ceil(column_count).max(1);
+    let width = column_count as f64 * PANEL_WIDTH;
+    let height = HEADER_HEIGHT + row_count as f64 * PANEL_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+        width, height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        width, height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="26" text-anchor="middle" font-size="18">{}</text>"#,
+        width / 2.0,
+        escape(title)
+    );
+    for (panel_index, panel) in panels.iter().enumerate() {
+        let scale = Scale {
+            left: (panel_index % column_count) as f64 * PANEL_WIDTH,
+            top: HEADER_HEIGHT + (panel_index / column_count) as f64 * PANEL_HEIGHT,
+            min_x,
+            max_x,
+        };
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="14">{}</text>"#,
+            scale.left + MARGIN_LEFT + Scale::plot_width() / 2.0,
+            scale.top + MARGIN_TOP - 14.0,
+            escape(&panel.title)
+        );
+        write_axes(&mut svg, &scale);
+        for (series_index, series) in panel.series.iter().enumerate() {
+            write_series(&mut svg, &scale, series, series_index);
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_series(name: &str, win_rates: &[f64]) -> Series {
+        Series {
+            name: name.to_string(),
+            points: win_rates
+                .iter()
+                .enumerate()
+                .map(|(index, win_rate)| Point {
+                    x: index + 3,
+                    win_rate: *win_rate,
+                    lower: win_rate - 0.01,
+                    upper: win_rate + 0.01,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn x_range() {
+        assert_eq!((0, 1), get_x_range(&[]));
+        let panel = Panel {
+            title: "single".to_string(),
+            series: vec![create_series("switch", &[0.5])],
+        };
+        assert_eq!((2, 4), get_x_range(&[panel]));
+    }
+
+    #[test]
+    fn scale() {
+        let scale = Scale {
+            left: 0.0,
+            top: 0.0,
+            min_x: 3,
+            max_x: 5,
+        };
+        assert_eq!(MARGIN_LEFT, scale.x(3));
+        assert_eq!(MARGIN_LEFT + Scale::plot_width(), scale.x(5));
+        assert_eq!(MARGIN_TOP, scale.y(1.0));
+        assert_eq!(MARGIN_TOP + Scale::plot_height(), scale.y(0.0));
+        assert_eq!(scale.y(0.0), scale.y(-0.5));
+    }
+
+    #[test]
+    fn render_panels() {
+        let panels = vec![
+            Panel {
+                title: "standard".to_string(),
+                series: vec![
+                    create_series("stay", &[0.33, 0.25, 0.2]),
+                    create_series("switch", &[0.67, 0.75, 0.8]),
+                ],
+            },
+            Panel {
+                title: "<fall>".to_string(),
+                series: vec![create_series("p=0.5 & more", &[0.33, 0.25, 0.2])],
+            },
+        ];
+        let svg = render_svg("Win rates", &panels);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1040\""));
+        assert!(svg.ends_with("</svg>\n"));
+        // Two polylines for the axes and one for every series
+        assert_eq!(5, svg.matches("<polyline").count());
+        assert_eq!(9, svg.matches("<circle").count());
+        assert!(svg.contains(">&lt;fall&gt;</text>"));
+        assert!(svg.contains(">p=0.5 &amp; more</text>"));
+    }
+}
@@ -0,0 +1,98 @@
+pub static TOO_FEW_DOORS_ERROR: &str = "At least 3 doors are necessary!";
+pub static INVALID_OPENED_DOOR_COUNT_ERROR: &str =
+    "The host has to open at least 1 and at most door count - 2 doors!";
+pub static INVALID_PRIZE_COUNT_ERROR: &str =
+    "There has to be at least 1 prize and enough empty doors for the host to open!";
+
+// The parameters of a game: the host opens opened_door_count empty doors from the door_count doors
+// and prize_count doors hide a prize
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct GameConfig {
+    door_count: usize,
+    opened_door_count: usize,
+    prize_count: usize,
+}
+
+impl GameConfig {
+    pub fn new(
+        door_count: usize,
+        opened_door_count: usize,
+        prize_count: usize,
+    ) -> Result<GameConfig, &'static str> {
+        if door_count < 3 {
+            return Err(TOO_FEW_DOORS_ERROR);
+        }
+        if opened_door_count < 1 || opened_door_count > door_count - 2 {
+            return Err(INVALID_OPENED_DOOR_COUNT_ERROR);
+        }
+        // The informed host has to be able to open the doors even if the contestant chose an empty
+        // door
+        if prize_count < 1 || prize_count > door_count - opened_door_count - 1 {
+            return Err(INVALID_PRIZE_COUNT_ERROR);
+        }
+        Ok(GameConfig {
+            door_count,
+            opened_door_count,
+            prize_count,
+        })
+    }
+
+    // The host opens every door except the chosen one and an other one, and there is one prize
+    pub fn classic(door_count: usize) -> Result<GameConfig, &'static str> {
+        if door_count < 3 {
+            return Err(TOO_FEW_DOORS_ERROR);
+        }
+        GameConfig::new(door_count, door_count - 2, 1)
+    }
+
+    pub fn door_count(&self) -> usize {
+        self.door_count
+    }
+
+    pub fn opened_door_count(&self) -> usize {
+        self.opened_door_count
+    }
+
+    pub fn prize_count(&self) -> usize {
+        self.prize_count
+    }
+
+    // The number of closed doors the contestant can switch to
+    pub fn switchable_door_count(&self) -> usize {
+        self.door_count - self.opened_door_count - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn valid_configs() {
+        let config = GameConfig::classic(5).unwrap();
+        assert_eq!(5, config.door_count());
+        assert_eq!(3, config.opened_door_count());
+        assert_eq!(1, config.prize_count());
+        assert_eq!(1, config.switchable_door_count());
+        assert_eq!(
+            4,
+            GameConfig::new(10, 5, 4).unwrap().switchable_door_count()
+        );
+    }
+
+    #[test]
+    fn invalid_configs() {
+        assert_eq!(Err(TOO_FEW_DOORS_ERROR), GameConfig::classic(2));
+        assert_eq!(Err(TOO_FEW_DOORS_ERROR), GameConfig::new(2, 1, 1));
+        assert_eq!(
+            Err(INVALID_OPENED_DOOR_COUNT_ERROR),
+            GameConfig::new(5, 0, 1)
+        );
+        assert_eq!(
+            Err(INVALID_OPENED_DOOR_COUNT_ERROR),
+            GameConfig::new(5, 4, 1)
+        );
+        assert_eq!(Err(INVALID_PRIZE_COUNT_ERROR), GameConfig::new(5, 2, 0));
+        assert_eq!(Err(INVALID_PRIZE_COUNT_ERROR), GameConfig::new(5, 2, 3));
+    }
+}
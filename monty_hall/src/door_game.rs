@@ -0,0 +1,495 @@
+use crate::config::GameConfig;
+use crate::host::{Host, HostAction};
+use crate::strategy::{Decision, Observation, Strategy};
+use rand::{Rng, RngCore};
+use std::fmt;
+use std::mem;
+
+pub static INVALID_DECISION_ERROR: &str = "Only an other closed door can be chosen!";
+pub static INVALID_DOOR_ERROR: &str = "Invalid door!";
+pub static INVALID_HOST_ACTION_ERROR: &str = "The host opened invalid doors!";
+pub static HOST_ALREADY_ACTED_ERROR: &str = "The host has already acted!";
+
+#[derive(Clone, Copy)]
+struct Door {
+    contains_prize: bool,
+    is_opened: bool,
+}
+
+impl Door {
+    fn new() -> Door {
+        Door {
+            contains_prize: false,
+            is_opened: false,
+        }
+    }
+}
+
+// The buffers of the game are reused by reset, so simulating many games doesn't allocate memory
+pub struct DoorGame {
+    config: GameConfig,
+    doors: Vec<Door>,
+    initial_door: usize,
+    chosen_door: usize,
+    opened_doors: Vec<usize>,
+    host_acted: bool,
+    switch_offered: bool,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct GameResult {
+    pub won: bool,
+    pub switch_offered: bool,
+    pub prize_revealed: bool,
+}
+
+impl DoorGame {
+    pub fn new(config: GameConfig, rng: &mut dyn RngCore) -> DoorGame {
+        let mut new_game = DoorGame {
+            config,
+            doors: vec![Door::new(); config.door_count()],
+            initial_door: 0,
+            chosen_door: 0,
+            opened_doors: Vec::with_capacity(config.opened_door_count()),
+            host_acted: false,
+            switch_offered: false,
+        };
+        new_game.reset(rng);
+        new_game
+    }
+
+    pub fn with_layout(
+        config: GameConfig,
+        chosen_door: usize,
+        prize_doors: &[usize],
+    ) -> Result<DoorGame, &'static str> {
+        let door_count = config.door_count();
+        if chosen_door >= door_count || prize_doors.iter().any(|door| *door >= door_count) {
+            return Err(INVALID_DOOR_ERROR);
+        }
+        let mut new_game = DoorGame {
+            config,
+            doors: vec![Door::new(); door_count],
+            initial_door: chosen_door,
+            chosen_door,
+            opened_doors: Vec::with_capacity(config.opened_door_count()),
+            host_acted: false,
+            switch_offered: false,
+        };
+        for door in prize_doors {
+            if new_game.doors[*door].contains_prize {
+                return Err(INVALID_DOOR_ERROR);
+            }
+            new_game.doors[*door].contains_prize = true;
+        }
+        if prize_doors.len() != config.prize_count() {
+            return Err(INVALID_DOOR_ERROR);
+        }
+        Ok(new_game)
+    }
+
+    // Starts a new game with a random layout
+    pub fn reset(&mut self, rng: &mut dyn RngCore) {
+        let door_count = self.doors.len();
+        for door in self.doors.iter_mut() {
+            *door = Door::new();
+        }
+        self.initial_door = rng.gen_range(0, door_count);
+        self.chosen_door = self.initial_door;
+        self.opened_doors.clear();
+        self.host_acted = false;
+        self.switch_offered = false;
+        // At least 2 doors are empty, so the rejection sampling finishes quickly
+        let mut placed_prize_count = 0;
+        while placed_prize_count < self.config.prize_count() {
+            let door = &mut self.doors[rng.gen_range(0, door_count)];
+            if !door.contains_prize {
+                door.contains_prize = true;
+                placed_prize_count += 1;
+            }
+        }
+    }
+
+    pub fn config(&self) -> GameConfig {
+        self.config
+    }
+
+    pub fn door_count(&self) -> usize {
+        self.doors.len()
+    }
+
+    // The door chosen before the host opened the doors
+    pub fn initial_door(&self) -> usize {
+        self.initial_door
+    }
+
+    pub fn chosen_door(&self) -> usize {
+        self.chosen_door
+    }
+
+    pub fn opened_doors(&self) -> &[usize] {
+        &self.opened_doors
+    }
+
+    // Replaces the randomly chosen initial door, e.g. when a person plays the game
+    pub fn choose_door(&mut self, door: usize) -> Result<(), &'static str> {
+        if self.host_acted {
+            return Err(HOST_ALREADY_ACTED_ERROR);
+        }
+        if door >= self.doors.len() {
+            return Err(INVALID_DOOR_ERROR);
+        }
+        self.initial_door = door;
+        self.chosen_door = door;
+        Ok(())
+    }
+
+    pub fn has_prize(&self, door: usize) -> bool {
+        self.doors[door].contains_prize
+    }
+
+    pub fn is_switch_offered(&self) -> bool {
+        self.switch_offered
+    }
+
+    pub fn is_prize_revealed(&self) -> bool {
+        self.opened_doors.iter().any(|door| self.has_prize(*door))
+    }
+
+    // Lets the host act, returns whether a switch is offered
+    pub fn open_doors(
+        &mut self,
+        host: &dyn Host,
+        rng: &mut dyn RngCore,
+    ) -> Result<bool, &'static str> {
+        if self.host_acted {
+            return Err(HOST_ALREADY_ACTED_ERROR);
+        }
+        self.host_acted = true;
+        let mut doors_to_open = mem::take(&mut self.opened_doors);
+        doors_to_open.clear();
+        let action = host.act(self, rng, &mut doors_to_open);
+        let result = self.mark_opened_doors(action, &doors_to_open);
+        if action == HostAction::NoOffer {
+            doors_to_open.clear();
+        }
+        self.opened_doors = doors_to_open;
+        self.switch_offered = result == Ok(true);
+        result
+    }
+
+    fn mark_opened_doors(
+        &mut self,
+        action: HostAction,
+        doors_to_open: &[usize],
+    ) -> Result<bool, &'static str> {
+        if action == HostAction::NoOffer {
+            return Ok(false);
+        }
+        if doors_to_open.len() != self.config.opened_door_count() {
+            return Err(INVALID_HOST_ACTION_ERROR);
+        }
+        for door in doors_to_open {
+            if *door >= self.doors.len() || *door == self.chosen_door || self.doors[*door].is_opened
+            {
+                return Err(INVALID_HOST_ACTION_ERROR);
+            }
+            self.doors[*door].is_opened = true;
+        }
+        Ok(true)
+    }
+
+    pub fn observation(&self) -> Observation<'_> {
+        Observation {
+            door_count: self.doors.len(),
+            chosen_door: self.chosen_door,
+            opened_doors: &self.opened_doors,
+            revealed_prize_count: self
+                .opened_doors
+                .iter()
+                .filter(|door| self.has_prize(**door))
+                .count(),
+        }
+    }
+
+    pub fn apply_decision(&mut self, decision: Decision) -> Result<(), &'static str> {
+        match decision {
+            Decision::Stay => Ok(()),
+            Decision::SwitchTo(door) => {
+                if !self.switch_offered
+                    || door >= self.doors.len()
+                    || door == self.chosen_door
+                    || self.doors[door].is_opened
+                {
+                    return Err(INVALID_DECISION_ERROR);
+                }
+                self.chosen_door = door;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn is_user_won(&self) -> bool {
+        self.doors[self.chosen_door].contains_prize
+    }
+
+    // Plays a new game with a random layout, the strategy is asked only when the host offers a
+    // switch
+    pub fn play(
+        &mut self,
+        host: &dyn Host,
+        strategy: &dyn Strategy,
+        rng: &mut dyn RngCore,
+    ) -> Result<GameResult, &'static str> {
+        self.reset(rng);
+        self.finish(host, strategy, rng)
+    }
+
+    fn finish(
+        &mut self,
+        host: &dyn Host,
+        strategy: &dyn Strategy,
+        rng: &mut dyn RngCore,
+    ) -> Result<GameResult, &'static str> {
+        if self.open_doors(host, rng)? {
+            let decision = strategy.decide(&self.observation(), rng);
+            self.apply_decision(decision)?;
+        }
+        Ok(self.result())
+    }
+
+    pub fn result(&self) -> GameResult {
+        GameResult {
+            won: self.is_user_won(),
+            switch_offered: self.switch_offered,
+            prize_revealed: self.is_prize_revealed(),
+        }
+    }
+}
+
+// Shows every door with the prizes and marks the chosen one
+impl fmt::Display for DoorGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for door in &self.doors {
+            write!(
+                f,
+                "[{}]",
+                if door.contains_prize {
+                    'J'
+                } else if door.is_opened {
+                    ' '
+                } else {
+                    'X'
+                }
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "{} ^ ", "   ".repeat(self.chosen_door))
+    }
+}
+
+// Plays a single game, DoorGame::play should be used to play many games
+pub fn play_game(
+    config: GameConfig,
+    host: &dyn Host,
+    strategy: &dyn Strategy,
+    rng: &mut dyn RngCore,
+) -> Result<GameResult, &'static str> {
+    DoorGame::new(config, rng).finish(host, strategy, rng)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::host::{AngelicHost, MontyFall, StandardHost};
+    use crate::strategy::RandomSwitch;
+    use crate::strategy::{AlwaysStay, AlwaysSwitch};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    struct LazyHost;
+
+    impl Host for LazyHost {
+        fn name(&self) -> String {
+            "lazy".to_string()
+        }
+
+        fn act(
+            &self,
+            game: &DoorGame,
+            _rng: &mut dyn RngCore,
+            doors_to_open: &mut Vec<usize>,
+        ) -> HostAction {
+            doors_to_open.push(game.chosen_door());
+            HostAction::OfferSwitch
+        }
+    }
+
+    fn classic(door_count: usize) -> GameConfig {
+        GameConfig::classic(door_count).unwrap()
+    }
+
+    fn count_wins(
+        config: GameConfig,
+        host: &dyn Host,
+        strategy: &dyn Strategy,
+        game_count: usize,
+    ) -> usize {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..game_count)
+            .filter(|_| play_game(config, host, strategy, &mut rng).unwrap().won)
+            .count()
+    }
+
+    #[test]
+    fn invalid_layouts() {
+        let config = GameConfig::new(4, 1, 2).unwrap();
+        for (chosen_door, prize_doors) in &[
+            (4, vec![0, 1]),
+            (0, vec![0, 4]),
+            (0, vec![1, 1]),
+            (0, vec![1]),
+        ] {
+            assert_eq!(
+                Some(INVALID_DOOR_ERROR),
+                DoorGame::with_layout(config, *chosen_door, prize_doors).err()
+            );
+        }
+    }
+
+    #[test]
+    fn open_doors() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            let mut game = DoorGame::new(classic(5), &mut rng);
+            assert_eq!(Ok(true), game.open_doors(&StandardHost, &mut rng));
+            let observation = game.observation();
+            assert_eq!(3, observation.opened_doors.len());
+            assert!(!observation.opened_doors.contains(&game.chosen_door()));
+            let other_doors: Vec<usize> = observation.other_closed_doors().collect();
+            assert_eq!(1, other_doors.len());
+            // Either the chosen or the other closed door contains the prize
+            assert!(game.is_user_won() || game.has_prize(other_doors[0]));
+            assert!(!game.is_prize_revealed());
+        }
+    }
+
+    #[test]
+    fn invalid_host_action() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = DoorGame::with_layout(classic(3), 0, &[1]).unwrap();
+        assert_eq!(
+            Err(INVALID_HOST_ACTION_ERROR),
+            game.open_doors(&LazyHost, &mut rng)
+        );
+    }
+
+    #[test]
+    fn no_offer() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = DoorGame::with_layout(classic(3), 1, &[1]).unwrap();
+        assert_eq!(Ok(false), game.open_doors(&AngelicHost, &mut rng));
+        assert_eq!(
+            Err(INVALID_DECISION_ERROR),
+            game.apply_decision(Decision::SwitchTo(0))
+        );
+        assert_eq!(
+            GameResult {
+                won: true,
+                switch_offered: false,
+                prize_revealed: false
+            },
+            game.result()
+        );
+    }
+
+    #[test]
+    fn choose_door() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut game = DoorGame::with_layout(classic(4), 0, &[2]).unwrap();
+        assert_eq!(Err(INVALID_DOOR_ERROR), game.choose_door(4));
+        assert_eq!(Ok(()), game.choose_door(2));
+        assert_eq!(2, game.initial_door());
+        assert_eq!(2, game.chosen_door());
+        game.open_doors(&StandardHost, &mut rng).unwrap();
+        assert!(!game.opened_doors().contains(&2));
+        assert_eq!(Err(HOST_ALREADY_ACTED_ERROR), game.choose_door(1));
+        assert_eq!(
+            Err(HOST_ALREADY_ACTED_ERROR),
+            game.open_doors(&StandardHost, &mut rng)
+        );
+        assert!(game.is_user_won());
+
+        game.reset(&mut rng);
+        assert_eq!(Ok(()), game.choose_door(3));
+    }
+
+    #[test]
+    fn apply_decision() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut game = DoorGame::new(classic(3), &mut rng);
+        game.open_doors(&StandardHost, &mut rng).unwrap();
+        let opened_door = game.opened_doors()[0];
+        let chosen_door = game.chosen_door();
+        let other_door = 3 - opened_door - chosen_door;
+        for invalid_door in &[opened_door, chosen_door, 3] {
+            assert_eq!(
+                Err(INVALID_DECISION_ERROR),
+                game.apply_decision(Decision::SwitchTo(*invalid_door))
+            );
+        }
+        assert_eq!(Ok(()), game.apply_decision(Decision::Stay));
+        assert_eq!(chosen_door, game.chosen_door());
+        assert_eq!(Ok(()), game.apply_decision(Decision::SwitchTo(other_door)));
+        assert_eq!(other_door, game.chosen_door());
+    }
+
+    #[test]
+    fn display() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = DoorGame::with_layout(classic(3), 1, &[2]).unwrap();
+        game.open_doors(&StandardHost, &mut rng).unwrap();
+        assert_eq!("[ ][X][J]\n    ^ ", game.to_string());
+    }
+
+    #[test]
+    fn switching_is_better() {
+        let stay_wins = count_wins(classic(3), &StandardHost, &AlwaysStay, 3000);
+        let switch_wins = count_wins(classic(3), &StandardHost, &AlwaysSwitch, 3000);
+        assert!(900 < stay_wins && stay_wins < 1100);
+        assert!(1900 < switch_wins && switch_wins < 2100);
+    }
+
+    #[test]
+    fn multiple_prizes() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let config = GameConfig::new(7, 2, 3).unwrap();
+        for _ in 0..100 {
+            let mut game = DoorGame::new(config, &mut rng);
+            assert_eq!(3, (0..7).filter(|door| game.has_prize(*door)).count());
+            game.open_doors(&StandardHost, &mut rng).unwrap();
+            assert_eq!(2, game.opened_doors().len());
+            assert_eq!(4, game.observation().other_closed_doors().count());
+        }
+        // 3/7 * (1 - 0.5) + 0.5 * 3 * 6 / (7 * 4) = 15/28
+        let wins = count_wins(config, &StandardHost, &RandomSwitch, 28000);
+        assert!(14500 < wins && wins < 15500);
+    }
+
+    #[test]
+    fn monty_fall_reveals_the_prize() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let results: Vec<GameResult> = (0..3000)
+            .map(|_| play_game(classic(3), &MontyFall, &AlwaysSwitch, &mut rng).unwrap())
+            .collect();
+        let revealed_count = results
+            .iter()
+            .filter(|result| result.prize_revealed)
+            .count();
+        assert!(900 < revealed_count && revealed_count < 1100);
+        assert!(results
+            .iter()
+            .filter(|result| result.prize_revealed)
+            .all(|result| !result.won));
+    }
+}
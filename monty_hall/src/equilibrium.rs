@@ -0,0 +1,633 @@
+// The game as a zero-sum game between the host and the contestant. The host knows whether the
+// first choice hides a prize and decides whether to offer a switch and how many prizes to reveal,
+// the contestant sees the number of revealed prizes and decides how likely it switches. The
+// positions of the opened doors are not part of the model: the host gains nothing by choosing
+// them in a way the contestant could learn from, so both of them choose the doors uniformly.
+use crate::analytic::{first_choice_win_probability, ratio};
+use crate::config::GameConfig;
+use crate::door_game::DoorGame;
+use crate::host::{Host, HostAction};
+use crate::linear_program::LinearProgram;
+use crate::strategy::{switch_to_random_door, Decision, Observation, Strategy};
+use num_rational::BigRational;
+use num_traits::{One, ToPrimitive, Zero};
+use rand::seq::IteratorRandom;
+use rand::{Rng, RngCore};
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum HostMove {
+    NoOffer,
+    // Opens doors with the given number of prizes behind them and offers a switch
+    OfferSwitch { revealed_prize_count: usize },
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct ZeroSumGame {
+    config: GameConfig,
+    host_can_decline: bool,
+    host_can_reveal_prizes: bool,
+}
+
+fn get_stay_win_probability(first_choice_won: bool) -> BigRational {
+    if first_choice_won {
+        BigRational::one()
+    } else {
+        BigRational::zero()
+    }
+}
+
+impl ZeroSumGame {
+    // By default the host can decline to offer a switch and can reveal prizes
+    pub fn new(config: GameConfig) -> ZeroSumGame {
+        ZeroSumGame {
+            config,
+            host_can_decline: true,
+            host_can_reveal_prizes: true,
+        }
+    }
+
+    pub fn config(&self) -> GameConfig {
+        self.config
+    }
+
+    pub fn set_host_can_decline(&mut self, host_can_decline: bool) {
+        self.host_can_decline = host_can_decline;
+    }
+
+    pub fn set_host_can_reveal_prizes(&mut self, host_can_reveal_prizes: bool) {
+        self.host_can_reveal_prizes = host_can_reveal_prizes;
+    }
+
+    pub fn host_moves(&self, first_choice_won: bool) -> Vec<HostMove> {
+        let opened_door_count = self.config.opened_door_count();
+        let other_prize_count = self.config.prize_count() - first_choice_won as usize;
+        let other_empty_door_count = self.config.door_count() - 1 - other_prize_count;
+        let max_revealed_prize_count = if self.host_can_reveal_prizes {
+            opened_door_count.min(other_prize_count)
+        } else {
+            0
+        };
+        let mut moves = Vec::new();
+        if self.host_can_decline {
+            moves.push(HostMove::NoOffer);
+        }
+        moves.extend(
+            (0..=max_revealed_prize_count)
+                .filter(|revealed_prize_count| {
+                    opened_door_count - revealed_prize_count <= other_empty_door_count
+                })
+                .map(|revealed_prize_count| HostMove::OfferSwitch {
+                    revealed_prize_count,
+                }),
+        );
+        moves
+    }
+
+    // The revealed prize counts the contestant might see, in increasing order
+    pub fn observable_prize_counts(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = [true, false]
+            .iter()
+            .flat_map(|first_choice_won| self.host_moves(*first_choice_won))
+            .filter_map(|host_move| match host_move {
+                HostMove::NoOffer => None,
+                HostMove::OfferSwitch {
+                    revealed_prize_count,
+                } => Some(revealed_prize_count),
+            })
+            .collect();
+        counts.sort_unstable();
+        counts.dedup();
+        counts
+    }
+
+    // The probability of winning by switching to a random closed door after the move of the host
+    fn switch_win_probability(
+        &self,
+        first_choice_won: bool,
+        revealed_prize_count: usize,
+    ) -> BigRational {
+        let remaining_prize_count =
+            self.config.prize_count() - first_choice_won as usize - revealed_prize_count;
+        ratio(remaining_prize_count, self.config.switchable_door_count())
+    }
+
+    pub fn win_probability(
+        &self,
+        first_choice_won: bool,
+        host_move: HostMove,
+        switch_probability: &BigRational,
+    ) -> BigRational {
+        let stay_win_probability = get_stay_win_probability(first_choice_won);
+        match host_move {
+            HostMove::NoOffer => stay_win_probability,
+            HostMove::OfferSwitch {
+                revealed_prize_count,
+            } => {
+                (BigRational::one() - switch_probability) * stay_win_probability
+                    + switch_probability
+                        * self.switch_win_probability(first_choice_won, revealed_prize_count)
+            }
+        }
+    }
+
+    // The variables of the linear program are the guaranteed win probabilities after a winning
+    // and a losing first choice, followed by the switching probabilities of the contestant for
+    // every observable prize count. The win probability after a first choice can't be more than
+    // the win probability after any move of the host, and the duals of these constraints give the
+    // strategy of the host.
+    pub fn solve(&self) -> Equilibrium {
+        let prize_counts = self.observable_prize_counts();
+        let variable_count = 2 + prize_counts.len();
+        let first_choice_probability = first_choice_win_probability(&self.config);
+        let mut objective = vec![BigRational::zero(); variable_count];
+        objective[0] = first_choice_probability.clone();
+        objective[1] = BigRational::one() - &first_choice_probability;
+        let mut program = LinearProgram::new(objective);
+
+        let mut constraint_moves = Vec::new();
+        for (variable_index, first_choice_won) in [(0, true), (1, false)] {
+            for host_move in self.host_moves(first_choice_won) {
+                let mut coefficients = vec![BigRational::zero(); variable_count];
+                coefficients[variable_index] = BigRational::one();
+                let stay_win_probability = get_stay_win_probability(first_choice_won);
+                if let HostMove::OfferSwitch {
+                    revealed_prize_count,
+                } = host_move
+                {
+                    let prize_count_index = prize_counts
+                        .iter()
+                        .position(|count| *count == revealed_prize_count)
+                        .expect("Every revealed prize count is observable!");
+                    coefficients[2 + prize_count_index] = &stay_win_probability
+                        - self.switch_win_probability(first_choice_won, revealed_prize_count);
+                }
+                program
+                    .add_constraint(coefficients, stay_win_probability)
+                    .expect("The constraints of the moves are valid!");
+                constraint_moves.push((first_choice_won, host_move));
+            }
+        }
+        for prize_count_index in 0..prize_counts.len() {
+            let mut coefficients = vec![BigRational::zero(); variable_count];
+            coefficients[2 + prize_count_index] = BigRational::one();
+            program
+                .add_constraint(coefficients, BigRational::one())
+                .expect("The constraints of the probabilities are valid!");
+        }
+
+        // The win probabilities are bounded, so the program is bounded too
+        let solution = program.maximize().expect("The win probability is bounded!");
+        let get_host_strategy = |first_choice_won: bool| {
+            let weights: Vec<(HostMove, BigRational)> = constraint_moves
+                .iter()
+                .zip(&solution.duals)
+                .filter(|((won, _), _)| *won == first_choice_won)
+                .map(|((_, host_move), dual)| (*host_move, dual.clone()))
+                .collect();
+            let total_weight = weights
+                .iter()
+                .fold(BigRational::zero(), |total, (_, weight)| total + weight);
+            weights
+                .into_iter()
+                .map(|(host_move, weight)| (host_move, weight / &total_weight))
+                .collect()
+        };
+        Equilibrium {
+            game: *self,
+            value: solution.value.clone(),
+            switch_probabilities: prize_counts
+                .into_iter()
+                .zip(solution.variables[2..].iter().cloned())
+                .collect(),
+            host_strategy_after_win: get_host_strategy(true),
+            host_strategy_after_loss: get_host_strategy(false),
+        }
+    }
+}
+
+pub struct Equilibrium {
+    game: ZeroSumGame,
+    value: BigRational,
+    switch_probabilities: Vec<(usize, BigRational)>,
+    host_strategy_after_win: Vec<(HostMove, BigRational)>,
+    host_strategy_after_loss: Vec<(HostMove, BigRational)>,
+}
+
+fn choose_move(strategy: &[(HostMove, f64)], rng: &mut dyn RngCore) -> HostMove {
+    let mut remaining_probability = rng.gen::<f64>();
+    for (host_move, probability) in strategy {
+        if remaining_probability < *probability {
+            return *host_move;
+        }
+        remaining_probability -= probability;
+    }
+    strategy[strategy.len() - 1].0
+}
+
+fn to_f64<T: Copy>(probabilities: &[(T, BigRational)]) -> Vec<(T, f64)> {
+    probabilities
+        .iter()
+        .map(|(item, probability)| (*item, probability.to_f64().unwrap_or_default()))
+        .collect()
+}
+
+impl Equilibrium {
+    pub fn game(&self) -> &ZeroSumGame {
+        &self.game
+    }
+
+    // The win probability of the contestant when both of them play optimally
+    pub fn value(&self) -> &BigRational {
+        &self.value
+    }
+
+    // The switching probability of the contestant for every observable revealed prize count
+    pub fn switch_probabilities(&self) -> &[(usize, BigRational)] {
+        &self.switch_probabilities
+    }
+
+    // The probabilities of the moves of the host, zero probabilities included
+    pub fn host_strategy(&self, first_choice_won: bool) -> &[(HostMove, BigRational)] {
+        if first_choice_won {
+            &self.host_strategy_after_win
+        } else {
+            &self.host_strategy_after_loss
+        }
+    }
+
+    pub fn host(&self) -> EquilibriumHost {
+        EquilibriumHost {
+            game: self.game,
+            strategy_after_win: self.host_strategy_after_win.clone(),
+            strategy_after_loss: self.host_strategy_after_loss.clone(),
+            sampled_strategy_after_win: to_f64(&self.host_strategy_after_win),
+            sampled_strategy_after_loss: to_f64(&self.host_strategy_after_loss),
+        }
+    }
+
+    pub fn contestant(&self) -> EquilibriumStrategy {
+        EquilibriumStrategy {
+            switch_probabilities: to_f64(&self.switch_probabilities),
+        }
+    }
+}
+
+// Plays the mixed strategy of the host, it is meant to be used with the config of the equilibrium
+pub struct EquilibriumHost {
+    game: ZeroSumGame,
+    strategy_after_win: Vec<(HostMove, BigRational)>,
+    strategy_after_loss: Vec<(HostMove, BigRational)>,
+    sampled_strategy_after_win: Vec<(HostMove, f64)>,
+    sampled_strategy_after_loss: Vec<(HostMove, f64)>,
+}
+
+fn append_random_doors(
+    doors: impl Iterator<Item = usize>,
+    count: usize,
+    rng: &mut dyn RngCore,
+    doors_to_open: &mut Vec<usize>,
+) {
+    let start = doors_to_open.len();
+    doors_to_open.resize(start + count, 0);
+    let chosen_count = doors.choose_multiple_fill(rng, &mut doors_to_open[start..]);
+    doors_to_open.truncate(start + chosen_count);
+}
+
+impl Host for EquilibriumHost {
+    fn name(&self) -> String {
+        "equilibrium".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        let chosen_door = game.chosen_door();
+        let first_choice_won = game.has_prize(chosen_door);
+        let strategy = if first_choice_won {
+            &self.sampled_strategy_after_win
+        } else {
+            &self.sampled_strategy_after_loss
+        };
+        let revealed_prize_count = match choose_move(strategy, rng) {
+            HostMove::NoOffer => return HostAction::NoOffer,
+            HostMove::OfferSwitch {
+                revealed_prize_count,
+            } => revealed_prize_count,
+        };
+        doors_to_open.clear();
+        let other_doors = (0..game.door_count()).filter(|door| *door != chosen_door);
+        append_random_doors(
+            other_doors.clone().filter(|door| game.has_prize(*door)),
+            revealed_prize_count,
+            rng,
+            doors_to_open,
+        );
+        append_random_doors(
+            other_doors.filter(|door| !game.has_prize(*door)),
+            game.config().opened_door_count() - revealed_prize_count,
+            rng,
+            doors_to_open,
+        );
+        HostAction::OfferSwitch
+    }
+
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        if *config != self.game.config() {
+            return None;
+        }
+        let get_conditional_probability = |first_choice_won: bool| {
+            let strategy = if first_choice_won {
+                &self.strategy_after_win
+            } else {
+                &self.strategy_after_loss
+            };
+            strategy
+                .iter()
+                .fold(BigRational::zero(), |total, (host_move, probability)| {
+                    total
+                        + probability
+                            * self.game.win_probability(
+                                first_choice_won,
+                                *host_move,
+                                switch_probability,
+                            )
+                })
+        };
+        let first_choice_probability = first_choice_win_probability(config);
+        Some(
+            get_conditional_probability(true) * &first_choice_probability
+                + get_conditional_probability(false)
+                    * (BigRational::one() - first_choice_probability),
+        )
+    }
+}
+
+// Switches with the equilibrium probability of the number of revealed prizes
+pub struct EquilibriumStrategy {
+    switch_probabilities: Vec<(usize, f64)>,
+}
+
+impl Strategy for EquilibriumStrategy {
+    fn name(&self) -> String {
+        "equilibrium".to_string()
+    }
+
+    fn decide(&self, observation: &Observation, rng: &mut dyn RngCore) -> Decision {
+        let switch_probability = self
+            .switch_probabilities
+            .iter()
+            .find(|(count, _)| *count == observation.revealed_prize_count)
+            .map_or(0.0, |(_, probability)| *probability);
+        if rng.gen_bool(switch_probability.clamp(0.0, 1.0)) {
+            switch_to_random_door(observation, rng)
+        } else {
+            Decision::Stay
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analytic::exact_win_probability;
+    use crate::host::{all_hosts, StandardHost};
+    use crate::runner::Simulation;
+    use crate::strategy::{AlwaysStay, AlwaysSwitch, RandomSwitch};
+
+    fn create_game(
+        config: GameConfig,
+        host_can_decline: bool,
+        host_can_reveal_prizes: bool,
+    ) -> ZeroSumGame {
+        let mut game = ZeroSumGame::new(config);
+        game.set_host_can_decline(host_can_decline);
+        game.set_host_can_reveal_prizes(host_can_reveal_prizes);
+        game
+    }
+
+    fn get_switch_probability(equilibrium: &Equilibrium, host_move: HostMove) -> BigRational {
+        match host_move {
+            HostMove::NoOffer => BigRational::zero(),
+            HostMove::OfferSwitch {
+                revealed_prize_count,
+            } => equilibrium
+                .switch_probabilities()
+                .iter()
+                .find(|(count, _)| *count == revealed_prize_count)
+                .map(|(_, probability)| probability.clone())
+                .unwrap(),
+        }
+    }
+
+    // Neither of them can do better by changing its strategy
+    fn check_equilibrium(equilibrium: &Equilibrium) {
+        let game = equilibrium.game();
+        let first_choice_probability = first_choice_win_probability(&game.config());
+        let get_probability = |first_choice_won: bool| {
+            if first_choice_won {
+                first_choice_probability.clone()
+            } else {
+                BigRational::one() - &first_choice_probability
+            }
+        };
+
+        // Against the strategy of the contestant every move of the host is at most as good as
+        // the value
+        let mut contestant_guarantee = BigRational::zero();
+        for first_choice_won in [true, false] {
+            let worst_win_probability = game
+                .host_moves(first_choice_won)
+                .into_iter()
+                .map(|host_move| {
+                    game.win_probability(
+                        first_choice_won,
+                        host_move,
+                        &get_switch_probability(equilibrium, host_move),
+                    )
+                })
+                .min()
+                .unwrap();
+            contestant_guarantee += get_probability(first_choice_won) * worst_win_probability;
+        }
+        assert_eq!(*equilibrium.value(), contestant_guarantee);
+
+        // Against the strategy of the host the best response of the contestant switches either
+        // always or never after every observation
+        let mut best_response_value = BigRational::zero();
+        for first_choice_won in [true, false] {
+            for (host_move, probability) in equilibrium.host_strategy(first_choice_won) {
+                best_response_value += get_probability(first_choice_won)
+                    * probability
+                    * game.win_probability(first_choice_won, *host_move, &BigRational::zero());
+            }
+        }
+        for (revealed_prize_count, _) in equilibrium.switch_probabilities() {
+            let mut gain = BigRational::zero();
+            for first_choice_won in [true, false] {
+                let host_move = HostMove::OfferSwitch {
+                    revealed_prize_count: *revealed_prize_count,
+                };
+                if let Some((_, probability)) = equilibrium
+                    .host_strategy(first_choice_won)
+                    .iter()
+                    .find(|(other_move, _)| *other_move == host_move)
+                {
+                    gain += get_probability(first_choice_won)
+                        * probability
+                        * (game.win_probability(first_choice_won, host_move, &BigRational::one())
+                            - game.win_probability(
+                                first_choice_won,
+                                host_move,
+                                &BigRational::zero(),
+                            ));
+                }
+            }
+            if gain > BigRational::zero() {
+                best_response_value += gain;
+            }
+        }
+        assert_eq!(*equilibrium.value(), best_response_value);
+    }
+
+    #[test]
+    fn host_moves() {
+        let config = GameConfig::new(6, 2, 2).unwrap();
+        let game = ZeroSumGame::new(config);
+        let offer = |revealed_prize_count| HostMove::OfferSwitch {
+            revealed_prize_count,
+        };
+        assert_eq!(
+            vec![HostMove::NoOffer, offer(0), offer(1)],
+            game.host_moves(true)
+        );
+        assert_eq!(
+            vec![HostMove::NoOffer, offer(0), offer(1), offer(2)],
+            game.host_moves(false)
+        );
+        assert_eq!(vec![0, 1, 2], game.observable_prize_counts());
+
+        let game = create_game(config, false, false);
+        assert_eq!(vec![offer(0)], game.host_moves(true));
+        assert_eq!(vec![offer(0)], game.host_moves(false));
+    }
+
+    #[test]
+    fn declining_host() {
+        // The host can offer a switch only when the first choice wins, so the contestant can't
+        // do better than staying
+        for (config, value) in &[
+            (GameConfig::classic(3).unwrap(), ratio(1, 3)),
+            (GameConfig::new(6, 2, 2).unwrap(), ratio(1, 3)),
+            (GameConfig::new(10, 3, 4).unwrap(), ratio(2, 5)),
+        ] {
+            for host_can_reveal_prizes in [true, false] {
+                let equilibrium = create_game(*config, true, host_can_reveal_prizes).solve();
+                assert_eq!(value, equilibrium.value());
+                check_equilibrium(&equilibrium);
+            }
+        }
+    }
+
+    #[test]
+    fn standard_rules() {
+        // The host has no choice, so the equilibrium is to switch always
+        let config = GameConfig::new(5, 2, 1).unwrap();
+        let equilibrium = create_game(config, false, false).solve();
+        assert_eq!(
+            exact_win_probability(&config, &StandardHost, &AlwaysSwitch).as_ref(),
+            Some(equilibrium.value())
+        );
+        assert_eq!(
+            &[(0, BigRational::one())][..],
+            equilibrium.switch_probabilities()
+        );
+        check_equilibrium(&equilibrium);
+    }
+
+    #[test]
+    fn revealing_host() {
+        // The host reveals the prize after half of the losing first choices, so switching is as
+        // good as staying
+        let equilibrium = create_game(GameConfig::classic(3).unwrap(), false, true).solve();
+        assert_eq!(ratio(1, 3), *equilibrium.value());
+        assert_eq!(
+            &[(0, BigRational::zero()), (1, BigRational::zero())][..],
+            equilibrium.switch_probabilities()
+        );
+        assert_eq!(
+            &[
+                (
+                    HostMove::OfferSwitch {
+                        revealed_prize_count: 0
+                    },
+                    ratio(1, 2)
+                ),
+                (
+                    HostMove::OfferSwitch {
+                        revealed_prize_count: 1
+                    },
+                    ratio(1, 2)
+                )
+            ][..],
+            equilibrium.host_strategy(false)
+        );
+        check_equilibrium(&equilibrium);
+
+        // The host can always take away the advantage of switching by revealing prizes
+        for config in &[
+            GameConfig::new(5, 1, 2).unwrap(),
+            GameConfig::new(7, 2, 4).unwrap(),
+            GameConfig::new(7, 3, 3).unwrap(),
+        ] {
+            let equilibrium = create_game(*config, false, true).solve();
+            assert_eq!(first_choice_win_probability(config), *equilibrium.value());
+            check_equilibrium(&equilibrium);
+        }
+    }
+
+    #[test]
+    fn equilibrium_host() {
+        let config = GameConfig::new(5, 1, 2).unwrap();
+        let equilibrium = create_game(config, false, true).solve();
+        let host = equilibrium.host();
+        // The contestant is indifferent between staying and switching against the host
+        for strategy in &[&AlwaysStay as &dyn Strategy, &AlwaysSwitch, &RandomSwitch] {
+            let exact_win_rate = exact_win_probability(&config, &host, *strategy).unwrap();
+            assert_eq!(*equilibrium.value(), exact_win_rate);
+            let statistics = Simulation::new(config, &host, *strategy, 1)
+                .run(100_000)
+                .unwrap();
+            let (lower, upper) = statistics.win_rate_confidence_interval().unwrap();
+            let exact_win_rate = exact_win_rate.to_f64().unwrap();
+            assert!(lower <= exact_win_rate && exact_win_rate <= upper);
+        }
+        assert_eq!(
+            None,
+            host.win_probability(&GameConfig::classic(3).unwrap(), &BigRational::one())
+        );
+    }
+
+    #[test]
+    fn equilibrium_strategy() {
+        // The built-in hosts follow the rules of the default game, so the contestant wins at
+        // least as often as the value against them
+        let config = GameConfig::new(5, 2, 2).unwrap();
+        let equilibrium = ZeroSumGame::new(config).solve();
+        let strategy = equilibrium.contestant();
+        let value = equilibrium.value().to_f64().unwrap();
+        for host in all_hosts() {
+            let statistics = Simulation::new(config, host.as_ref(), &strategy, 2)
+                .run(50_000)
+                .unwrap();
+            let (_, upper) = statistics.win_rate_confidence_interval().unwrap();
+            assert!(value <= upper, "{}", host.name());
+        }
+    }
+}
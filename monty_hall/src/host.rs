@@ -0,0 +1,378 @@
+use crate::analytic::{first_choice_win_probability, offered_switch_win_probability};
+use crate::config::GameConfig;
+use crate::door_game::DoorGame;
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+use rand::seq::IteratorRandom;
+use rand::RngCore;
+
+pub static INVALID_HOST_ERROR: &str = "Invalid host!";
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum HostAction {
+    // The host opens the doors it listed and lets the contestant switch
+    OfferSwitch,
+    // The game ends with the originally chosen door
+    NoOffer,
+}
+
+pub trait Host: Send + Sync {
+    fn name(&self) -> String;
+    // The host knows where the prizes are and has to list as many doors to open as the config of
+    // the game requires when it offers a switch, but never the chosen one. The list is reused
+    // between the games to avoid allocations.
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction;
+
+    // The exact win probability against a strategy which switches to a uniformly chosen closed
+    // door with the given probability, None if it is unknown
+    fn win_probability(
+        &self,
+        _config: &GameConfig,
+        _switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        None
+    }
+
+    // Whether the host might open a door with a prize, the traces of the hosts which never do it
+    // are rejected when they contain a revealed prize
+    fn may_reveal_prizes(&self) -> bool {
+        true
+    }
+
+    // Whether the host opens the same doors whenever the layout is the same, the traces of these
+    // hosts are rejected when they contain other doors
+    fn opens_fixed_doors(&self) -> bool {
+        false
+    }
+}
+
+// The win probability when the host offers a switch only after the specified first choices
+fn get_win_probability(
+    config: &GameConfig,
+    switch_probability: &BigRational,
+    offer_after_win: bool,
+    offer_after_loss: bool,
+) -> BigRational {
+    let first_choice_probability = first_choice_win_probability(config);
+    let get_conditional_probability = |first_choice_won: bool, offer: bool| {
+        if offer {
+            offered_switch_win_probability(config, first_choice_won, switch_probability)
+        } else if first_choice_won {
+            BigRational::one()
+        } else {
+            BigRational::zero()
+        }
+    };
+    get_conditional_probability(true, offer_after_win) * &first_choice_probability
+        + get_conditional_probability(false, offer_after_loss)
+            * (BigRational::one() - first_choice_probability)
+}
+
+fn get_doors_to_open_count(game: &DoorGame) -> usize {
+    game.config().opened_door_count()
+}
+
+fn other_doors(game: &DoorGame) -> impl Iterator<Item = usize> + '_ {
+    (0..game.door_count()).filter(move |door| *door != game.chosen_door())
+}
+
+fn other_empty_doors(game: &DoorGame) -> impl Iterator<Item = usize> + '_ {
+    other_doors(game).filter(move |door| !game.has_prize(*door))
+}
+
+fn open_random_doors(
+    doors: impl Iterator<Item = usize>,
+    count: usize,
+    rng: &mut dyn RngCore,
+    doors_to_open: &mut Vec<usize>,
+) -> HostAction {
+    doors_to_open.resize(count, 0);
+    let chosen_count = doors.choose_multiple_fill(rng, doors_to_open);
+    doors_to_open.truncate(chosen_count);
+    HostAction::OfferSwitch
+}
+
+fn open_random_empty_doors(
+    game: &DoorGame,
+    rng: &mut dyn RngCore,
+    doors_to_open: &mut Vec<usize>,
+) -> HostAction {
+    open_random_doors(
+        other_empty_doors(game),
+        get_doors_to_open_count(game),
+        rng,
+        doors_to_open,
+    )
+}
+
+// The host of the original problem: opens empty doors chosen uniformly at random
+pub struct StandardHost;
+
+impl Host for StandardHost {
+    fn name(&self) -> String {
+        "standard".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        open_random_empty_doors(game, rng, doors_to_open)
+    }
+
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        Some(get_win_probability(config, switch_probability, true, true))
+    }
+
+    fn may_reveal_prizes(&self) -> bool {
+        false
+    }
+}
+
+// "Monty Fall": the host doesn't know where the prizes are, so it might reveal them
+pub struct MontyFall;
+
+impl Host for MontyFall {
+    fn name(&self) -> String {
+        "fall".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        open_random_doors(
+            other_doors(game),
+            get_doors_to_open_count(game),
+            rng,
+            doors_to_open,
+        )
+    }
+
+    // Switching to a random closed door is the same as choosing a random door from the not chosen
+    // ones, so the strategy doesn't matter
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        _switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        Some(first_choice_win_probability(config))
+    }
+}
+
+// "Monty Crawl": the host opens the empty doors with the lowest indices, so the door left closed
+// tells something about the location of the prizes
+pub struct MontyCrawl;
+
+impl Host for MontyCrawl {
+    fn name(&self) -> String {
+        "crawl".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        _rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        doors_to_open.extend(other_empty_doors(game).take(get_doors_to_open_count(game)));
+        HostAction::OfferSwitch
+    }
+
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        Some(get_win_probability(config, switch_probability, true, true))
+    }
+
+    fn may_reveal_prizes(&self) -> bool {
+        false
+    }
+
+    fn opens_fixed_doors(&self) -> bool {
+        true
+    }
+}
+
+// Offers a switch only when the contestant has chosen an empty door
+pub struct AngelicHost;
+
+impl Host for AngelicHost {
+    fn name(&self) -> String {
+        "angelic".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        if game.has_prize(game.chosen_door()) {
+            HostAction::NoOffer
+        } else {
+            open_random_empty_doors(game, rng, doors_to_open)
+        }
+    }
+
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        Some(get_win_probability(config, switch_probability, false, true))
+    }
+
+    fn may_reveal_prizes(&self) -> bool {
+        false
+    }
+}
+
+// Offers a switch only when the contestant has chosen a prize
+pub struct DevilishHost;
+
+impl Host for DevilishHost {
+    fn name(&self) -> String {
+        "devilish".to_string()
+    }
+
+    fn act(
+        &self,
+        game: &DoorGame,
+        rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        if game.has_prize(game.chosen_door()) {
+            open_random_empty_doors(game, rng, doors_to_open)
+        } else {
+            HostAction::NoOffer
+        }
+    }
+
+    fn win_probability(
+        &self,
+        config: &GameConfig,
+        switch_probability: &BigRational,
+    ) -> Option<BigRational> {
+        Some(get_win_probability(config, switch_probability, true, false))
+    }
+
+    fn may_reveal_prizes(&self) -> bool {
+        false
+    }
+}
+
+// Accepted names: standard, fall, crawl, angelic and devilish
+pub fn parse_host(name: &str) -> Result<Box<dyn Host>, &'static str> {
+    match name {
+        "standard" => Ok(Box::new(StandardHost)),
+        "fall" => Ok(Box::new(MontyFall)),
+        "crawl" => Ok(Box::new(MontyCrawl)),
+        "angelic" => Ok(Box::new(AngelicHost)),
+        "devilish" => Ok(Box::new(DevilishHost)),
+        _ => Err(INVALID_HOST_ERROR),
+    }
+}
+
+pub fn all_hosts() -> Vec<Box<dyn Host>> {
+    vec![
+        Box::new(StandardHost),
+        Box::new(MontyFall),
+        Box::new(MontyCrawl),
+        Box::new(AngelicHost),
+        Box::new(DevilishHost),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::GameConfig;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn act(host: &dyn Host, game: &DoorGame, rng: &mut StdRng) -> Option<Vec<usize>> {
+        let mut doors_to_open = Vec::new();
+        match host.act(game, rng, &mut doors_to_open) {
+            HostAction::OfferSwitch => Some(doors_to_open),
+            HostAction::NoOffer => None,
+        }
+    }
+
+    #[test]
+    fn informed_hosts_open_empty_doors() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for host in &[&StandardHost as &dyn Host, &MontyCrawl] {
+            assert!(!host.may_reveal_prizes());
+            for _ in 0..100 {
+                let game = DoorGame::new(GameConfig::classic(6).unwrap(), &mut rng);
+                let opened_doors = act(*host, &game, &mut rng).unwrap();
+                assert_eq!(4, opened_doors.len());
+                assert!(opened_doors
+                    .iter()
+                    .all(|door| *door != game.chosen_door() && !game.has_prize(*door)));
+            }
+        }
+    }
+
+    #[test]
+    fn monty_crawl_prefers_lower_indices() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let game = DoorGame::with_layout(GameConfig::classic(4).unwrap(), 3, &[3]).unwrap();
+        assert_eq!(vec![0, 1], act(&MontyCrawl, &game, &mut rng).unwrap());
+    }
+
+    #[test]
+    fn monty_fall_might_reveal_the_prize() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let game = DoorGame::with_layout(GameConfig::classic(3).unwrap(), 0, &[1]).unwrap();
+        assert!(MontyFall.may_reveal_prizes());
+        let mut revealed_count = 0;
+        for _ in 0..100 {
+            let opened_doors = act(&MontyFall, &game, &mut rng).unwrap();
+            assert_eq!(1, opened_doors.len());
+            assert_ne!(0, opened_doors[0]);
+            if opened_doors[0] == 1 {
+                revealed_count += 1;
+            }
+        }
+        assert!(30 < revealed_count && revealed_count < 70);
+    }
+
+    #[test]
+    fn angelic_and_devilish_hosts() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let right_choice = DoorGame::with_layout(GameConfig::classic(3).unwrap(), 1, &[1]).unwrap();
+        let wrong_choice = DoorGame::with_layout(GameConfig::classic(3).unwrap(), 1, &[2]).unwrap();
+        assert_eq!(None, act(&AngelicHost, &right_choice, &mut rng));
+        assert_eq!(vec![0], act(&AngelicHost, &wrong_choice, &mut rng).unwrap());
+        assert_eq!(None, act(&DevilishHost, &wrong_choice, &mut rng));
+        assert_eq!(
+            1,
+            act(&DevilishHost, &right_choice, &mut rng).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn parse_hosts() {
+        for host in all_hosts() {
+            assert_eq!(host.name(), parse_host(&host.name()).unwrap().name());
+        }
+        assert!(parse_host("lazy").is_err());
+    }
+}
@@ -0,0 +1,388 @@
+// Lets a person play the games against any host and compares the results with the optimal strategy
+use monty_hall::{
+    exact_win_probability, AlwaysStay, AlwaysSwitch, Decision, DoorGame, GameConfig, GameResult,
+    Host, Statistics, Strategy,
+};
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+use rand::RngCore;
+use std::io::{self, BufRead, Write};
+
+pub struct Session<'a> {
+    config: GameConfig,
+    host: &'a dyn Host,
+    optimal_strategy: Box<dyn Strategy>,
+    optimal_win_rate: Option<BigRational>,
+    user_statistics: Statistics,
+    // The results of the optimal strategy in the games played by the user
+    optimal_statistics: Statistics,
+}
+
+// The win probability is linear in the switching probability, so either always staying or always
+// switching is optimal. Switching is chosen when the win probability is unknown.
+fn find_optimal_strategy(
+    config: &GameConfig,
+    host: &dyn Host,
+) -> (Box<dyn Strategy>, Option<BigRational>) {
+    let stay_win_rate = exact_win_probability(config, host, &AlwaysStay);
+    let switch_win_rate = exact_win_probability(config, host, &AlwaysSwitch);
+    match (stay_win_rate, switch_win_rate) {
+        (Some(stay_win_rate), Some(switch_win_rate)) if stay_win_rate > switch_win_rate => {
+            (Box::new(AlwaysStay), Some(stay_win_rate))
+        }
+        (_, switch_win_rate) => (Box::new(AlwaysSwitch), switch_win_rate),
+    }
+}
+
+fn format_percentage(count: u64, total: u64) -> String {
+    if total == 0 {
+        "-".to_string()
+    } else {
+        format!("{:.2}%", count as f64 / total as f64 * 100.0)
+    }
+}
+
+fn format_doors(doors: &[usize]) -> String {
+    let numbers: Vec<String> = doors.iter().map(|door| (door + 1).to_string()).collect();
+    numbers.join(", ")
+}
+
+fn get_door_width(game: &DoorGame) -> usize {
+    game.door_count().to_string().len().max(3)
+}
+
+// Draws the doors with their numbers, the prizes behind the closed doors are shown only when the
+// game is revealed
+fn write_doors(game: &DoorGame, reveal: bool, output: &mut dyn Write) -> io::Result<()> {
+    let width = get_door_width(game);
+    let mut numbers = Vec::new();
+    let mut doors = Vec::new();
+    for door in 0..game.door_count() {
+        let is_opened = game.opened_doors().contains(&door);
+        let symbol = if (is_opened || reveal) && game.has_prize(door) {
+            "[J]"
+        } else if is_opened {
+            "[ ]"
+        } else if reveal {
+            "[X]"
+        } else {
+            "[?]"
+        };
+        numbers.push(format!("{:^width$}", door + 1, width = width));
+        doors.push(format!("{:^width$}", symbol, width = width));
+    }
+    writeln!(output, "{}", numbers.join(" "))?;
+    writeln!(output, "{}", doors.join(" "))
+}
+
+// Draws the doors and marks the chosen one
+fn write_chosen_doors(game: &DoorGame, reveal: bool, output: &mut dyn Write) -> io::Result<()> {
+    write_doors(game, reveal, output)?;
+    let width = get_door_width(game);
+    writeln!(
+        output,
+        "{}{:^width$}",
+        " ".repeat((width + 1) * game.chosen_door()),
+        "^",
+        width = width
+    )
+}
+
+// Returns None when the input is over or the user quits
+fn read_answer(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    prompt: &str,
+) -> io::Result<Option<String>> {
+    write!(output, "{}", prompt)?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        writeln!(output)?;
+        return Ok(None);
+    }
+    let answer = line.trim().to_lowercase();
+    if answer == "q" || answer == "quit" {
+        return Ok(None);
+    }
+    Ok(Some(answer))
+}
+
+fn parse_door(answer: &str, door_count: usize) -> Option<usize> {
+    match answer.parse::<usize>() {
+        Ok(door) if 1 <= door && door <= door_count => Some(door - 1),
+        _ => None,
+    }
+}
+
+impl<'a> Session<'a> {
+    pub fn new(config: GameConfig, host: &'a dyn Host) -> Session<'a> {
+        let (optimal_strategy, optimal_win_rate) = find_optimal_strategy(&config, host);
+        Session {
+            config,
+            host,
+            optimal_strategy,
+            optimal_win_rate,
+            user_statistics: Statistics::new(),
+            optimal_statistics: Statistics::new(),
+        }
+    }
+
+    // Plays games until the input is over or the user quits
+    pub fn run(
+        &mut self,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+        rng: &mut dyn RngCore,
+    ) -> io::Result<()> {
+        writeln!(
+            output,
+            "There are {} doors and {} {}. Type q to quit.",
+            self.config.door_count(),
+            self.config.prize_count(),
+            if self.config.prize_count() == 1 {
+                "prize"
+            } else {
+                "prizes"
+            }
+        )?;
+        let mut game = DoorGame::new(self.config, rng);
+        while self.play_game(&mut game, input, output, rng)? {
+            self.write_tally(output)?;
+            game.reset(rng);
+        }
+        writeln!(output, "Final results:")?;
+        self.write_tally(output)
+    }
+
+    // Returns false when the game is abandoned
+    fn play_game(
+        &mut self,
+        game: &mut DoorGame,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+        rng: &mut dyn RngCore,
+    ) -> io::Result<bool> {
+        writeln!(
+            output,
+            "\nGame {} with the {} host",
+            self.user_statistics.game_count + 1,
+            self.host.name()
+        )?;
+        write_doors(game, false, output)?;
+        let door_count = game.door_count();
+        let chosen_door = loop {
+            let prompt = format!("Choose a door (1-{}): ", door_count);
+            match read_answer(input, output, &prompt)? {
+                None => return Ok(false),
+                Some(answer) => match parse_door(&answer, door_count) {
+                    Some(door) => break door,
+                    None => writeln!(output, "There is no such door!")?,
+                },
+            }
+        };
+        game.choose_door(chosen_door)
+            .expect("The door is validated before it is chosen!");
+
+        let switch_offered = game.open_doors(self.host, rng).map_err(io::Error::other)?;
+        if switch_offered {
+            writeln!(
+                output,
+                "The host opened {} {} and offers a switch.",
+                if game.opened_doors().len() == 1 {
+                    "door"
+                } else {
+                    "doors"
+                },
+                format_doors(game.opened_doors())
+            )?;
+            if game.is_prize_revealed() {
+                writeln!(output, "Oops, the host revealed a prize!")?;
+            }
+        } else {
+            writeln!(
+                output,
+                "The host doesn't open any door and doesn't offer a switch."
+            )?;
+        }
+        write_chosen_doors(game, false, output)?;
+
+        let optimal_result = self.play_optimally(game, rng);
+        if switch_offered {
+            let decision = loop {
+                let prompt = format!(
+                    "Type the number of the door to switch to or press enter to keep door {}: ",
+                    chosen_door + 1
+                );
+                match read_answer(input, output, &prompt)? {
+                    None => return Ok(false),
+                    Some(answer) if answer.is_empty() || answer == "stay" => break Decision::Stay,
+                    Some(answer) => match parse_door(&answer, door_count) {
+                        Some(door) if door == chosen_door => break Decision::Stay,
+                        Some(door)
+                            if game
+                                .observation()
+                                .other_closed_doors()
+                                .any(|other| other == door) =>
+                        {
+                            break Decision::SwitchTo(door)
+                        }
+                        _ => writeln!(output, "Only an other closed door can be chosen!")?,
+                    },
+                }
+            };
+            game.apply_decision(decision)
+                .expect("The decision is validated before it is applied!");
+        }
+
+        write_chosen_doors(game, true, output)?;
+        writeln!(
+            output,
+            "{}",
+            if game.is_user_won() {
+                "You win!"
+            } else {
+                "You lose!"
+            }
+        )?;
+        self.user_statistics.add(game.result());
+        self.optimal_statistics.add(optimal_result);
+        Ok(true)
+    }
+
+    // What the optimal strategy would achieve after the same actions of the host
+    fn play_optimally(&self, game: &DoorGame, rng: &mut dyn RngCore) -> GameResult {
+        let mut result = game.result();
+        if game.is_switch_offered() {
+            if let Decision::SwitchTo(door) = self.optimal_strategy.decide(&game.observation(), rng)
+            {
+                result.won = game.has_prize(door);
+            }
+        }
+        result
+    }
+
+    fn write_tally(&self, output: &mut dyn Write) -> io::Result<()> {
+        let user = &self.user_statistics;
+        let optimal = &self.optimal_statistics;
+        writeln!(
+            output,
+            "You won {} of {} games ({}).",
+            user.win_count,
+            user.game_count,
+            format_percentage(user.win_count, user.game_count)
+        )?;
+        write!(
+            output,
+            "The optimal strategy ({}) would have won {} of them ({})",
+            self.optimal_strategy.name(),
+            optimal.win_count,
+            format_percentage(optimal.win_count, optimal.game_count)
+        )?;
+        match &self.optimal_win_rate {
+            Some(win_rate) => writeln!(
+                output,
+                ", its expected win rate is {} ({:.2}%).",
+                win_rate,
+                win_rate.to_f64().unwrap_or_default() * 100.0
+            ),
+            None => writeln!(output, "."),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use monty_hall::{DevilishHost, StandardHost};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn play<'a>(config: GameConfig, host: &'a dyn Host, input: &str) -> (Session<'a>, String) {
+        let mut session = Session::new(config, host);
+        let mut output = Vec::new();
+        let mut rng = StdRng::seed_from_u64(11);
+        session
+            .run(&mut input.as_bytes(), &mut output, &mut rng)
+            .unwrap();
+        (session, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn optimal_strategies() {
+        let config = GameConfig::classic(3).unwrap();
+        let (strategy, win_rate) = find_optimal_strategy(&config, &StandardHost);
+        assert_eq!("switch", strategy.name());
+        assert_eq!(Some(monty_hall::ratio(2, 3)), win_rate);
+        let (strategy, win_rate) = find_optimal_strategy(&config, &DevilishHost);
+        assert_eq!("stay", strategy.name());
+        assert_eq!(Some(monty_hall::ratio(1, 3)), win_rate);
+    }
+
+    #[test]
+    fn draw_doors() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut game = DoorGame::with_layout(GameConfig::classic(3).unwrap(), 1, &[2]).unwrap();
+        game.open_doors(&StandardHost, &mut rng).unwrap();
+        let mut output = Vec::new();
+        write_doors(&game, false, &mut output).unwrap();
+        write_chosen_doors(&game, true, &mut output).unwrap();
+        assert_eq!(
+            " 1   2   3 \n[ ] [?] [?]\n 1   2   3 \n[ ] [X] [J]\n     ^ \n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn play_games() {
+        let config = GameConfig::classic(3).unwrap();
+        // The invalid answers are asked again, the third game is abandoned
+        let (session, output) = play(config, &StandardHost, "4\n1\n\n2\nx\n2\n");
+        assert_eq!(2, session.user_statistics.game_count);
+        assert_eq!(2, session.optimal_statistics.game_count);
+        assert_eq!(1, output.matches("There is no such door!").count());
+        assert_eq!(
+            1,
+            output
+                .matches("Only an other closed door can be chosen!")
+                .count()
+        );
+        assert!(output.contains("Game 3 with the standard host"));
+        assert!(output.ends_with(&format!(
+            "Final results:\nYou won {} of 2 games ({}).\nThe optimal strategy (switch) would have won {} of them ({}), its expected win rate is 2/3 (66.67%).\n",
+            session.user_statistics.win_count,
+            format_percentage(session.user_statistics.win_count, 2),
+            session.optimal_statistics.win_count,
+            format_percentage(session.optimal_statistics.win_count, 2),
+        )));
+    }
+
+    #[test]
+    fn optimal_strategy_in_the_same_games() {
+        let config = GameConfig::classic(3).unwrap();
+        // Always keeping the first door and switching are complementary in the classic game
+        let input = "1\n\n".repeat(50);
+        let (session, _) = play(config, &StandardHost, &input);
+        let user = &session.user_statistics;
+        let optimal = &session.optimal_statistics;
+        assert_eq!(50, user.game_count);
+        assert_eq!(50, user.win_count + optimal.win_count);
+    }
+
+    #[test]
+    fn no_offer_and_quit() {
+        let config = GameConfig::classic(3).unwrap();
+        // The empty answers are invalid doors when no switch is offered
+        let (session, output) = play(config, &DevilishHost, &"2\n\n".repeat(10));
+        assert!(session.user_statistics.game_count >= 5);
+        assert_eq!(
+            session.user_statistics.win_count,
+            session.optimal_statistics.win_count
+        );
+        assert!(output.contains("The host doesn't open any door and doesn't offer a switch."));
+
+        let (session, output) = play(config, &StandardHost, "q\n");
+        assert_eq!(0, session.user_statistics.game_count);
+        assert!(output.contains("You won 0 of 0 games (-)."));
+    }
+}
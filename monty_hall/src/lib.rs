@@ -0,0 +1,32 @@
+mod analytic;
+mod config;
+mod door_game;
+mod equilibrium;
+mod host;
+mod linear_program;
+mod runner;
+mod statistics;
+mod strategy;
+
+pub use analytic::{exact_win_probability, probability_from_f64, ratio, win_probability};
+pub use config::{
+    GameConfig, INVALID_OPENED_DOOR_COUNT_ERROR, INVALID_PRIZE_COUNT_ERROR, TOO_FEW_DOORS_ERROR,
+};
+pub use door_game::{
+    play_game, DoorGame, GameResult, HOST_ALREADY_ACTED_ERROR, INVALID_DECISION_ERROR,
+    INVALID_DOOR_ERROR, INVALID_HOST_ACTION_ERROR,
+};
+pub use equilibrium::{Equilibrium, EquilibriumHost, EquilibriumStrategy, HostMove, ZeroSumGame};
+pub use host::{
+    all_hosts, parse_host, AngelicHost, DevilishHost, Host, HostAction, MontyCrawl, MontyFall,
+    StandardHost, INVALID_HOST_ERROR,
+};
+pub use linear_program::{
+    LinearProgram, Solution, INVALID_CONSTRAINT_ERROR, UNBOUNDED_PROGRAM_ERROR,
+};
+pub use runner::{create_chunk_rng, Simulation, GAMES_PER_CHUNK, INVALID_THREAD_COUNT_ERROR};
+pub use statistics::Statistics;
+pub use strategy::{
+    parse_strategy, AlwaysStay, AlwaysSwitch, Decision, Observation, RandomSwitch, Strategy,
+    SwitchWithProbability, INVALID_STRATEGY_ERROR,
+};
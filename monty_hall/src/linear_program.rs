@@ -0,0 +1,237 @@
+// A small simplex solver with exact rational arithmetic
+use num_rational::BigRational;
+use num_traits::{Signed, Zero};
+
+pub static INVALID_CONSTRAINT_ERROR: &str =
+    "Every constraint needs a coefficient for every variable and a nonnegative bound!";
+pub static UNBOUNDED_PROGRAM_ERROR: &str = "The linear program is unbounded!";
+
+// Maximizes the objective subject to constraints of the form a * x <= b and x >= 0. The bounds of
+// the constraints have to be nonnegative, so the origin is always feasible.
+pub struct LinearProgram {
+    objective: Vec<BigRational>,
+    constraints: Vec<(Vec<BigRational>, BigRational)>,
+}
+
+pub struct Solution {
+    pub value: BigRational,
+    pub variables: Vec<BigRational>,
+    // The optimal solution of the dual program, one value for every constraint
+    pub duals: Vec<BigRational>,
+}
+
+// The rows of the constraints followed by the objective row, the columns of the variables
+// followed by the slack variables and the bound
+struct Tableau {
+    rows: Vec<Vec<BigRational>>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn objective_row(&self) -> &[BigRational] {
+        &self.rows[self.rows.len() - 1]
+    }
+
+    // Bland's rule: the first improving column enters and the first row with the smallest ratio
+    // leaves, which prevents cycling
+    fn find_pivot(&self) -> Result<Option<(usize, usize)>, &'static str> {
+        let column_count = self.objective_row().len() - 1;
+        let entering =
+            match (0..column_count).find(|column| self.objective_row()[*column].is_negative()) {
+                Some(column) => column,
+                None => return Ok(None),
+            };
+        let mut leaving: Option<(usize, BigRational)> = None;
+        for (row_index, row) in self.rows[..self.basis.len()].iter().enumerate() {
+            if !row[entering].is_positive() {
+                continue;
+            }
+            let ratio = &row[column_count] / &row[entering];
+            let is_better = match &leaving {
+                None => true,
+                Some((leaving_row, smallest_ratio)) => {
+                    ratio < *smallest_ratio
+                        || (ratio == *smallest_ratio
+                            && self.basis[row_index] < self.basis[*leaving_row])
+                }
+            };
+            if is_better {
+                leaving = Some((row_index, ratio));
+            }
+        }
+        match leaving {
+            Some((row_index, _)) => Ok(Some((row_index, entering))),
+            None => Err(UNBOUNDED_PROGRAM_ERROR),
+        }
+    }
+
+    fn pivot(&mut self, pivot_row: usize, pivot_column: usize) {
+        let pivot = self.rows[pivot_row][pivot_column].clone();
+        for value in self.rows[pivot_row].iter_mut() {
+            *value /= &pivot;
+        }
+        let normalized_row = self.rows[pivot_row].clone();
+        for (row_index, row) in self.rows.iter_mut().enumerate() {
+            if row_index == pivot_row || row[pivot_column].is_zero() {
+                continue;
+            }
+            let factor = row[pivot_column].clone();
+            for (value, pivot_value) in row.iter_mut().zip(&normalized_row) {
+                *value -= &factor * pivot_value;
+            }
+        }
+        self.basis[pivot_row] = pivot_column;
+    }
+}
+
+impl LinearProgram {
+    pub fn new(objective: Vec<BigRational>) -> LinearProgram {
+        LinearProgram {
+            objective,
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn add_constraint(
+        &mut self,
+        coefficients: Vec<BigRational>,
+        bound: BigRational,
+    ) -> Result<(), &'static str> {
+        if coefficients.len() != self.objective.len() || bound.is_negative() {
+            return Err(INVALID_CONSTRAINT_ERROR);
+        }
+        self.constraints.push((coefficients, bound));
+        Ok(())
+    }
+
+    fn create_tableau(&self) -> Tableau {
+        let variable_count = self.objective.len();
+        let constraint_count = self.constraints.len();
+        let mut rows = Vec::with_capacity(constraint_count + 1);
+        for (constraint_index, (coefficients, bound)) in self.constraints.iter().enumerate() {
+            let mut row = coefficients.clone();
+            row.extend((0..constraint_count).map(|slack_index| {
+                if slack_index == constraint_index {
+                    BigRational::from_integer(1.into())
+                } else {
+                    BigRational::zero()
+                }
+            }));
+            row.push(bound.clone());
+            rows.push(row);
+        }
+        let mut objective_row: Vec<BigRational> = self.objective.iter().map(|c| -c).collect();
+        objective_row.resize(variable_count + constraint_count + 1, BigRational::zero());
+        rows.push(objective_row);
+        Tableau {
+            rows,
+            basis: (variable_count..variable_count + constraint_count).collect(),
+        }
+    }
+
+    pub fn maximize(&self) -> Result<Solution, &'static str> {
+        let mut tableau = self.create_tableau();
+        while let Some((pivot_row, pivot_column)) = tableau.find_pivot()? {
+            tableau.pivot(pivot_row, pivot_column);
+        }
+
+        let variable_count = self.objective.len();
+        let objective_row = tableau.objective_row();
+        let bound_column = objective_row.len() - 1;
+        let mut variables = vec![BigRational::zero(); variable_count];
+        for (row_index, basic_column) in tableau.basis.iter().enumerate() {
+            if *basic_column < variable_count {
+                variables[*basic_column] = tableau.rows[row_index][bound_column].clone();
+            }
+        }
+        Ok(Solution {
+            value: objective_row[bound_column].clone(),
+            variables,
+            duals: objective_row[variable_count..bound_column].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::analytic::ratio;
+
+    fn integers(values: &[usize]) -> Vec<BigRational> {
+        values.iter().map(|value| ratio(*value, 1)).collect()
+    }
+
+    #[test]
+    fn invalid_constraints() {
+        let mut program = LinearProgram::new(integers(&[1, 1]));
+        assert_eq!(
+            Err(INVALID_CONSTRAINT_ERROR),
+            program.add_constraint(integers(&[1]), ratio(1, 1))
+        );
+        assert_eq!(
+            Err(INVALID_CONSTRAINT_ERROR),
+            program.add_constraint(integers(&[1, 1]), -ratio(1, 1))
+        );
+    }
+
+    #[test]
+    fn unbounded_program() {
+        let mut program = LinearProgram::new(integers(&[1, 1]));
+        program
+            .add_constraint(integers(&[1, 0]), ratio(3, 1))
+            .unwrap();
+        assert_eq!(Some(UNBOUNDED_PROGRAM_ERROR), program.maximize().err());
+    }
+
+    #[test]
+    fn optimal_solution() {
+        // max 3x + 5y subject to x <= 4, 2y <= 12, 3x + 2y <= 18
+        let mut program = LinearProgram::new(integers(&[3, 5]));
+        program
+            .add_constraint(integers(&[1, 0]), ratio(4, 1))
+            .unwrap();
+        program
+            .add_constraint(integers(&[0, 2]), ratio(12, 1))
+            .unwrap();
+        program
+            .add_constraint(integers(&[3, 2]), ratio(18, 1))
+            .unwrap();
+        let solution = program.maximize().unwrap();
+        assert_eq!(ratio(36, 1), solution.value);
+        assert_eq!(integers(&[2, 6]), solution.variables);
+        // The dual has the same optimum
+        assert_eq!(vec![ratio(0, 1), ratio(3, 2), ratio(1, 1)], solution.duals);
+    }
+
+    #[test]
+    fn degenerate_program() {
+        // A degenerate program where the textbook pivoting rule cycles
+        let mut program = LinearProgram::new(vec![
+            ratio(3, 4),
+            -ratio(150, 1),
+            ratio(1, 50),
+            -ratio(6, 1),
+        ]);
+        program
+            .add_constraint(
+                vec![ratio(1, 4), -ratio(60, 1), -ratio(1, 25), ratio(9, 1)],
+                ratio(0, 1),
+            )
+            .unwrap();
+        program
+            .add_constraint(
+                vec![ratio(1, 2), -ratio(90, 1), -ratio(1, 50), ratio(3, 1)],
+                ratio(0, 1),
+            )
+            .unwrap();
+        program
+            .add_constraint(integers(&[0, 0, 1, 0]), ratio(1, 1))
+            .unwrap();
+        let solution = program.maximize().unwrap();
+        assert_eq!(ratio(1, 20), solution.value);
+        assert_eq!(
+            vec![ratio(1, 25), ratio(0, 1), ratio(1, 1), ratio(0, 1)],
+            solution.variables
+        );
+    }
+}
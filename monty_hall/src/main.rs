@@ -1,169 +1,592 @@
-use std::env;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use monty_hall::{
+    all_hosts, parse_host, parse_strategy, AlwaysStay, AlwaysSwitch, DoorGame, GameConfig, Host,
+    RandomSwitch, Simulation, Strategy, ZeroSumGame, TOO_FEW_DOORS_ERROR,
+};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
 
-struct Door {
-    contains_jackpot: bool,
-    is_opened: bool,
+mod chart;
+mod interactive;
+mod report;
+mod sweep;
+mod trace;
+
+use interactive::Session;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use report::{write_equilibrium, write_report, write_reports, Format, HostReport, Report};
+use sweep::{create_chart, Sweep};
+use trace::Tracer;
+
+fn parse_strategy_arg(name: &str) -> Result<Arc<dyn Strategy>, String> {
+    parse_strategy(name)
+        .map(Arc::from)
+        .map_err(|error| error.to_string())
 }
 
-impl Door {
-    fn new() -> Door {
-        Door {
-            contains_jackpot: false,
-            is_opened: false,
-        }
+fn parse_door_count(value: &str) -> Result<usize, String> {
+    let door_count = value.parse::<usize>().map_err(|error| error.to_string())?;
+    if door_count < 3 {
+        return Err(TOO_FEW_DOORS_ERROR.to_string());
     }
+    Ok(door_count)
 }
 
-struct DoorGame {
-    doors: Vec<Door>,
-    chosen_door: usize,
+fn parse_positive(value: &str) -> Result<u64, String> {
+    match value.parse::<u64>().map_err(|error| error.to_string())? {
+        0 => Err("The value has to be positive!".to_string()),
+        value => Ok(value),
+    }
 }
 
-impl DoorGame {
-    fn new(door_count: usize) -> DoorGame {
-        let mut new_game = DoorGame {
-            doors: Vec::with_capacity(door_count),
-            chosen_door: 0,
-        };
-        for _i in 0..door_count {
-            new_game.doors.push(Door::new());
-        }
-        new_game.chosen_door = new_game.rand_door();
-        let jackpot_holder = new_game.rand_door();
-        new_game.doors[jackpot_holder].contains_jackpot = true;
-        return new_game;
-    }
-
-    fn open_doors(self: &mut DoorGame) {
-        let mut num_of_opened_doors = 0;
-        for _i in 0..self.doors.len() {
-            if _i != self.chosen_door
-                && !self.doors[_i].is_opened
-                && !self.doors[_i].contains_jackpot
-            {
-                self.doors[_i].is_opened = true;
-                num_of_opened_doors += 1;
-                if num_of_opened_doors == self.doors.len() - 2 {
-                    break;
-                }
-            }
+fn parse_host_arg(name: &str) -> Result<Arc<dyn Host>, String> {
+    parse_host(name)
+        .map(Arc::from)
+        .map_err(|error| error.to_string())
+}
+
+/// Simulates the Monty Hall problem and its variants
+#[derive(Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    simulate: SimulateArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Simulates every combination of door counts, strategies and hosts, writes the win rates as
+    /// CSV and optionally draws them as an SVG line chart
+    Sweep(SweepArgs),
+    /// Plays the games interactively: the user chooses a door, the host opens doors and the user
+    /// decides whether to switch. The results are compared with the optimal strategy.
+    Play(PlayArgs),
+    /// Recomputes the reports from a trace file written by the simulation
+    Analyze(AnalyzeArgs),
+    /// Solves the game as a zero-sum game between the contestant and an adversarial host: prints
+    /// the win probability both of them can guarantee and their mixed strategies
+    Equilibrium(EquilibriumArgs),
+}
+
+// The options which are common in every mode that runs simulations
+#[derive(Args)]
+struct RunArgs {
+    /// The number of simulated games per host
+    #[arg(long, default_value_t = 100_000, value_parser = parse_positive)]
+    games: u64,
+
+    /// The behaviour of the host: standard, fall (ignorant host, might reveal a prize), crawl
+    /// (prefers the doors with lower indices), angelic (offers a switch only to losers) or devilish
+    /// (offers a switch only to winners). Can be repeated, every host is simulated by default.
+    #[arg(long = "host", value_parser = parse_host_arg)]
+    hosts: Vec<Arc<dyn Host>>,
+
+    /// The seed of the random number generators, the same seed gives the same results regardless
+    /// of the number of threads [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// The number of threads simulating the games [default: number of available cores]
+    #[arg(long, value_parser = parse_positive)]
+    threads: Option<u64>,
+}
+
+impl RunArgs {
+    fn hosts(&mut self) -> Vec<Arc<dyn Host>> {
+        if self.hosts.is_empty() {
+            all_hosts().into_iter().map(Arc::from).collect()
+        } else {
+            mem::take(&mut self.hosts)
         }
+    }
 
-        assert_eq!(self.doors.len() - 2, num_of_opened_doors);
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(rand::random::<u64>)
     }
 
-    fn change_chosen(self: &mut DoorGame) {
-        let old_chosen = self.chosen_door;
-        for _i in (0..self.doors.len()).rev() {
-            if _i != self.chosen_door && !self.doors[_i].is_opened {
-                self.chosen_door = _i;
-                break;
-            }
-        }
-        assert_ne!(old_chosen, self.chosen_door);
-        assert!(!self.doors[self.chosen_door].is_opened);
+    fn thread_count(&self) -> usize {
+        self.threads.map_or_else(
+            || thread::available_parallelism().map_or(1, |count| count.get()),
+            |thread_count| thread_count as usize,
+        )
     }
+}
+
+// The options which describe the game in every mode that uses a single configuration
+#[derive(Args)]
+struct ConfigArgs {
+    /// The number of doors, at least 3
+    #[arg(long, default_value_t = 3, value_parser = parse_door_count)]
+    doors: usize,
+
+    /// The number of doors opened by the host, between 1 and doors - 2 [default: doors - 2]
+    #[arg(long)]
+    opened_doors: Option<usize>,
 
-    fn is_user_won(self: &DoorGame) -> bool {
-        self.doors[self.chosen_door].contains_jackpot
+    /// The number of doors hiding a prize, at most doors - opened doors - 1
+    #[arg(long, default_value_t = 1)]
+    prizes: usize,
+}
+
+impl ConfigArgs {
+    fn config(&self) -> GameConfig {
+        let opened_door_count = self.opened_doors.unwrap_or(self.doors - 2);
+        GameConfig::new(self.doors, opened_door_count, self.prizes)
+            .unwrap_or_else(|error| exit_with_error(ErrorKind::ValueValidation, error))
     }
+}
 
-    fn print(self: &DoorGame) {
-        for door in &self.doors {
-            print!(
-                "[{}]",
-                if door.contains_jackpot {
-                    'J'
-                } else if door.is_opened {
-                    ' '
-                } else {
-                    'X'
-                }
-            )
-        }
-        println!()
+#[derive(Args)]
+struct SimulateArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    /// What the user does when a switch is offered: stay, switch (to a random closed door),
+    /// random (flips a coin) or p=<probability> (switches with the given probability)
+    #[arg(long, default_value = "switch", value_parser = parse_strategy_arg)]
+    strategy: Arc<dyn Strategy>,
+
+    #[command(flatten)]
+    run: RunArgs,
+
+    /// The format of the report
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Prints every game, the games are simulated on a single thread (text format only)
+    #[arg(long)]
+    print_games: bool,
+
+    /// Writes every game to the given file as a line of JSON with the prize doors, the initial
+    /// choice, the opened doors, the final choice and the outcome. The games are simulated on a
+    /// single thread.
+    #[arg(long)]
+    trace: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SweepArgs {
+    /// The smallest number of doors, at least 3
+    #[arg(long, default_value_t = 3, value_parser = parse_door_count)]
+    min_doors: usize,
+
+    /// The largest number of doors
+    #[arg(long, default_value_t = 10, value_parser = parse_door_count)]
+    max_doors: usize,
+
+    /// The number of doors opened by the host for every door count [default: doors - 2]
+    #[arg(long)]
+    opened_doors: Option<usize>,
+
+    /// The number of doors hiding a prize
+    #[arg(long, default_value_t = 1)]
+    prizes: usize,
+
+    /// A strategy of the user, see the strategies of the simulation. Can be repeated, stay,
+    /// random and switch are simulated by default.
+    #[arg(long = "strategy", value_parser = parse_strategy_arg)]
+    strategies: Vec<Arc<dyn Strategy>>,
+
+    #[command(flatten)]
+    run: RunArgs,
+
+    /// The CSV file of the win rates [default: the standard output]
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// The SVG file of the chart, a panel is drawn for every host with a line for every strategy
+    #[arg(long)]
+    chart: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    /// The behaviour of the host, see the hosts of the simulation
+    #[arg(long, default_value = "standard", value_parser = parse_host_arg)]
+    host: Arc<dyn Host>,
+
+    /// The seed of the random number generator [default: random]
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Args)]
+struct AnalyzeArgs {
+    /// The trace file, - reads the standard input
+    trace: PathBuf,
+
+    /// The format of the report
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Args)]
+struct EquilibriumArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    /// The host has to offer a switch in every game
+    #[arg(long)]
+    host_must_offer: bool,
+
+    /// The host can open empty doors only
+    #[arg(long)]
+    host_opens_empty_doors: bool,
+}
+
+fn print_game(game: &DoorGame) {
+    println!("{}", game);
+    if !game.is_switch_offered() {
+        println!("The host doesn't offer a switch!");
+    } else if game.chosen_door() != game.initial_door() {
+        println!("The user switched his choice!");
+    } else {
+        println!("The user kept his choice!");
     }
+    println!(
+        "{}",
+        if game.is_user_won() {
+            "The user wins!"
+        } else {
+            "The user loses!"
+        }
+    );
+}
+
+fn exit_with_error(kind: ErrorKind, message: &str) -> ! {
+    Cli::command().error(kind, message).exit()
+}
 
-    fn print_with_chosen(self: &DoorGame) {
-        self.print();
-        print!(
-            "{}",
-            (0..self.chosen_door).map(|_| "   ").collect::<String>()
+fn simulate(mut args: SimulateArgs) {
+    let config = args.config.config();
+    if args.print_games && args.format != Format::Text {
+        exit_with_error(
+            ErrorKind::ArgumentConflict,
+            "--print-games can be used only with the text format",
         );
-        println!(" ^ ");
     }
 
-    fn rand_door(self: &DoorGame) -> usize {
-        rand::random::<usize>() % self.doors.len()
+    let hosts = args.run.hosts();
+    let seed = args.run.seed();
+    let thread_count = args.run.thread_count();
+    let strategy = args.strategy.as_ref();
+    let mut tracer = args.trace.as_ref().map(|path| {
+        let file = File::create(path)
+            .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
+        Tracer::new(BufWriter::new(file), strategy.name(), seed)
+    });
+    let host_reports = hosts
+        .iter()
+        .map(|host| {
+            let mut simulation = Simulation::new(config, host.as_ref(), strategy, seed);
+            simulation.set_thread_count(thread_count)?;
+            let statistics = if args.print_games || tracer.is_some() {
+                if let Some(tracer) = &mut tracer {
+                    tracer.set_host(host.name());
+                }
+                simulation.run_sequentially(args.run.games, |game| {
+                    if args.print_games {
+                        print_game(game);
+                    }
+                    if let Some(tracer) = &mut tracer {
+                        tracer.trace(game);
+                    }
+                })?
+            } else {
+                simulation.run(args.run.games)?
+            };
+            Ok(HostReport::new(
+                &config,
+                host.as_ref(),
+                strategy,
+                statistics,
+            ))
+        })
+        .collect::<Result<Vec<HostReport>, &'static str>>()
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::ValueValidation, error));
+    if let Some(tracer) = tracer {
+        tracer
+            .finish()
+            .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
     }
+
+    let report = Report {
+        config,
+        strategy: strategy.name(),
+        seed,
+        host_reports,
+    };
+    write_report(&report, args.format, &mut io::stdout().lock())
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
 }
 
-fn convert_string_to_bool(arg: &String) -> Option<bool> {
-    if !(arg == "t" || arg == "f") {
-        return None;
+fn sweep(mut args: SweepArgs) {
+    let strategies: Vec<Arc<dyn Strategy>> = if args.strategies.is_empty() {
+        vec![
+            Arc::new(AlwaysStay),
+            Arc::new(RandomSwitch),
+            Arc::new(AlwaysSwitch),
+        ]
     } else {
-        Some(arg == "t")
+        mem::take(&mut args.strategies)
+    };
+    let sweep = Sweep {
+        door_counts: args.min_doors..=args.max_doors,
+        opened_door_count: args.opened_doors,
+        prize_count: args.prizes,
+        strategies,
+        hosts: args.run.hosts(),
+        game_count: args.run.games,
+        seed: args.run.seed(),
+        thread_count: args.run.thread_count(),
+    };
+    let reports = sweep
+        .run()
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::ValueValidation, error));
+
+    let csv_result = match &args.output {
+        Some(path) => File::create(path)
+            .and_then(|file| write_reports(&reports, Format::Csv, &mut BufWriter::new(file))),
+        None => write_reports(&reports, Format::Csv, &mut io::stdout().lock()),
+    };
+    csv_result.unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
+    if let Some(path) = &args.chart {
+        fs::write(path, create_chart(&reports))
+            .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
     }
 }
 
-fn convert_arg_to_bool(arg: &Option<&String>) -> Option<bool> {
-    match arg {
-        None => None,
-        Some(str_arg) => convert_string_to_bool(str_arg),
-    }
+fn play(args: PlayArgs) {
+    let config = args.config.config();
+    let mut rng = ChaCha8Rng::seed_from_u64(args.seed.unwrap_or_else(rand::random::<u64>));
+    let mut session = Session::new(config, args.host.as_ref());
+    session
+        .run(&mut io::stdin().lock(), &mut io::stdout(), &mut rng)
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
+}
+
+fn analyze(args: AnalyzeArgs) {
+    let result = if args.trace.as_os_str() == "-" {
+        trace::analyze(&mut io::stdin().lock())
+    } else {
+        File::open(&args.trace)
+            .map_err(|error| error.to_string())
+            .and_then(|file| trace::analyze(&mut BufReader::new(file)))
+    };
+    let reports =
+        result.unwrap_or_else(|error| exit_with_error(ErrorKind::ValueValidation, &error));
+    write_reports(&reports, args.format, &mut io::stdout().lock())
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
+}
+
+fn equilibrium(args: EquilibriumArgs) {
+    let config = args.config.config();
+    let mut game = ZeroSumGame::new(config);
+    game.set_host_can_decline(!args.host_must_offer);
+    game.set_host_can_reveal_prizes(!args.host_opens_empty_doors);
+    write_equilibrium(&game.solve(), &mut io::stdout().lock())
+        .unwrap_or_else(|error| exit_with_error(ErrorKind::Io, &error.to_string()));
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect::<Vec<String>>();
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Sweep(args)) => sweep(args),
+        Some(Command::Play(args)) => play(args),
+        Some(Command::Analyze(args)) => analyze(args),
+        Some(Command::Equilibrium(args)) => equilibrium(args),
+        None => simulate(cli.simulate),
+    }
+}
 
-    let n = args[1]
-        .parse::<usize>()
-        .expect("Please provide the number of doors as the first parameter!");
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    let game_count = args[2]
-        .parse::<usize>()
-        .expect("Please provide the number of games as second parameter!");
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("monty_hall").chain(args.iter().copied()))
+    }
 
-    let switch_choice = convert_arg_to_bool(&args.get(3))
-        .expect("Please provide either 't' (true) or 'f' (false) as third parameter to determine whether the user switches his choice or not!");
+    fn parse_sweep(args: &[&str]) -> SweepArgs {
+        match parse(args).unwrap().command {
+            Some(Command::Sweep(args)) => args,
+            _ => panic!("The sweep subcommand is not parsed!"),
+        }
+    }
 
-    let print_dbg = convert_arg_to_bool(&args.get(4)).unwrap_or(false);
+    fn get_host_names(args: &RunArgs) -> Vec<String> {
+        args.hosts.iter().map(|host| host.name()).collect()
+    }
 
-    let mut win_count = 0;
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
 
-    for _count in 0..game_count {
-        let mut game = DoorGame::new(n);
+    #[test]
+    fn default_values() {
+        let cli = parse(&[]).unwrap();
+        assert!(cli.command.is_none());
+        let args = cli.simulate;
+        assert_eq!(3, args.config.doors);
+        assert_eq!(None, args.config.opened_doors);
+        assert_eq!(1, args.config.prizes);
+        assert_eq!(100_000, args.run.games);
+        assert_eq!("switch", args.strategy.name());
+        assert!(args.run.hosts.is_empty());
+        assert_eq!(Format::Text, args.format);
+    }
 
-        game.open_doors();
-        if print_dbg {
-            println!("Doors after opening:");
-            game.print_with_chosen();
-        }
+    #[test]
+    fn named_options() {
+        let args = parse(&[
+            "--doors=10",
+            "--opened-doors",
+            "4",
+            "--prizes=2",
+            "--strategy=p=0.25",
+            "--host=fall",
+            "--host",
+            "angelic",
+            "--seed=7",
+            "--threads=2",
+            "--format=csv",
+        ])
+        .unwrap()
+        .simulate;
+        assert_eq!(10, args.config.doors);
+        assert_eq!(Some(4), args.config.opened_doors);
+        assert_eq!(2, args.config.prizes);
+        assert_eq!("p=0.25", args.strategy.name());
+        assert_eq!(vec!["fall", "angelic"], get_host_names(&args.run));
+        assert_eq!(Some(7), args.run.seed);
+        assert_eq!(Some(2), args.run.threads);
+        assert_eq!(Format::Csv, args.format);
+    }
 
-        if switch_choice {
-            if print_dbg {
-                println!("The user switches his choice:");
-            }
-            game.change_chosen();
-            if print_dbg {
-                game.print_with_chosen();
-            }
+    #[test]
+    fn invalid_options() {
+        for args in &[
+            &["--doors=2"][..],
+            &["--games=0"],
+            &["--games=many"],
+            &["--strategy=sometimes"],
+            &["--host=lazy"],
+            &["--threads=0"],
+            &["--format=xml"],
+            &["--unknown"],
+            &["--doors=4", "sweep"],
+            &["sweep", "--min-doors=2"],
+            &["sweep", "--format=csv"],
+            &["play", "--host=all"],
+            &["play", "--games=10"],
+            &["equilibrium", "--host=standard"],
+        ] {
+            assert!(parse(args).is_err(), "{:?} should be rejected", args);
         }
+    }
 
-        let win = game.is_user_won();
-        if win {
-            win_count += 1;
-        }
-        if print_dbg {
-            println!(
-                "{}",
-                if win {
-                    "The user wins!"
-                } else {
-                    "The user loses!"
-                }
-            );
-        }
+    #[test]
+    fn sweep_options() {
+        let args = parse_sweep(&["sweep"]);
+        assert_eq!(3, args.min_doors);
+        assert_eq!(10, args.max_doors);
+        assert!(args.strategies.is_empty());
+        assert_eq!(None, args.output);
+        assert_eq!(None, args.chart);
+
+        let args = parse_sweep(&[
+            "sweep",
+            "--min-doors=4",
+            "--max-doors=20",
+            "--opened-doors=1",
+            "--strategy=stay",
+            "--strategy=p=0.9",
+            "--host=crawl",
+            "--games=500",
+            "--output=rates.csv",
+            "--chart=rates.svg",
+        ]);
+        assert_eq!(4, args.min_doors);
+        assert_eq!(20, args.max_doors);
+        assert_eq!(Some(1), args.opened_doors);
+        let strategy_names: Vec<String> = args
+            .strategies
+            .iter()
+            .map(|strategy| strategy.name())
+            .collect();
+        assert_eq!(vec!["stay", "p=0.9"], strategy_names);
+        assert_eq!(vec!["crawl"], get_host_names(&args.run));
+        assert_eq!(500, args.run.games);
+        assert_eq!(Some(PathBuf::from("rates.csv")), args.output);
+        assert_eq!(Some(PathBuf::from("rates.svg")), args.chart);
+    }
+
+    #[test]
+    fn play_options() {
+        let args = match parse(&["play", "--doors=5", "--prizes=2", "--host=fall"])
+            .unwrap()
+            .command
+        {
+            Some(Command::Play(args)) => args,
+            _ => panic!("The play subcommand is not parsed!"),
+        };
+        assert_eq!(5, args.config.doors);
+        assert_eq!(None, args.config.opened_doors);
+        assert_eq!(2, args.config.prizes);
+        assert_eq!("fall", args.host.name());
+        assert_eq!(None, args.seed);
+    }
+
+    #[test]
+    fn trace_options() {
+        let args = parse(&["--trace=games.jsonl", "--host=crawl"])
+            .unwrap()
+            .simulate;
+        assert_eq!(Some(PathBuf::from("games.jsonl")), args.trace);
+
+        let args = match parse(&["analyze", "games.jsonl", "--format=json"])
+            .unwrap()
+            .command
+        {
+            Some(Command::Analyze(args)) => args,
+            _ => panic!("The analyze subcommand is not parsed!"),
+        };
+        assert_eq!(PathBuf::from("games.jsonl"), args.trace);
+        assert_eq!(Format::Json, args.format);
+        assert!(parse(&["analyze"]).is_err());
+    }
+
+    #[test]
+    fn equilibrium_options() {
+        let args = match parse(&[
+            "equilibrium",
+            "--doors=5",
+            "--prizes=2",
+            "--host-must-offer",
+        ])
+        .unwrap()
+        .command
+        {
+            Some(Command::Equilibrium(args)) => args,
+            _ => panic!("The equilibrium subcommand is not parsed!"),
+        };
+        assert_eq!(5, args.config.doors);
+        assert_eq!(None, args.config.opened_doors);
+        assert_eq!(2, args.config.prizes);
+        assert!(args.host_must_offer);
+        assert!(!args.host_opens_empty_doors);
     }
-    println!("The user won {} times!", win_count);
 }
@@ -0,0 +1,393 @@
+// The results of a simulation in the supported output formats
+use monty_hall::{
+    exact_win_probability, AlwaysStay, AlwaysSwitch, Equilibrium, GameConfig, Host, HostMove,
+    Statistics, Strategy,
+};
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+use serde_json::{json, Value};
+use std::io::{self, Write};
+use std::slice;
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, clap::ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+pub struct HostReport {
+    pub host: String,
+    pub statistics: Statistics,
+    pub exact_win_rate: Option<BigRational>,
+}
+
+impl HostReport {
+    pub fn new(
+        config: &GameConfig,
+        host: &dyn Host,
+        strategy: &dyn Strategy,
+        statistics: Statistics,
+    ) -> HostReport {
+        HostReport {
+            host: host.name(),
+            statistics,
+            exact_win_rate: exact_win_probability(config, host, strategy),
+        }
+    }
+
+    fn absolute_error(&self) -> Option<f64> {
+        let exact_win_rate = self.exact_win_rate.as_ref()?.to_f64()?;
+        Some((self.statistics.win_rate()? - exact_win_rate).abs())
+    }
+}
+
+pub struct Report {
+    pub config: GameConfig,
+    pub strategy: String,
+    pub seed: u64,
+    pub host_reports: Vec<HostReport>,
+}
+
+const CSV_HEADER: &str = "host,strategy,doors,opened_doors,prizes,games,wins,win_rate,ci_lower,ci_upper,exact_win_rate,exact_win_rate_value,absolute_error,offered_games,offered_win_rate,revealed_games";
+
+fn format_percentage(rate: Option<f64>) -> String {
+    match rate {
+        Some(rate) => format!("{:.2}%", rate * 100.0),
+        None => "-".to_string(),
+    }
+}
+
+fn format_csv_value<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(String::new, |value| value.to_string())
+}
+
+fn write_text(report: &Report, output: &mut dyn Write) -> io::Result<()> {
+    writeln!(output, "seed: {}", report.seed)?;
+    for host_report in &report.host_reports {
+        let statistics = &host_report.statistics;
+        writeln!(output, "{} host:", host_report.host)?;
+        writeln!(
+            output,
+            "  the user won {} times in {} games",
+            statistics.win_count, statistics.game_count
+        )?;
+        match statistics.win_rate_confidence_interval() {
+            Some((lower, upper)) => writeln!(
+                output,
+                "  simulated win rate: {} (95% confidence interval: {} - {})",
+                format_percentage(statistics.win_rate()),
+                format_percentage(Some(lower)),
+                format_percentage(Some(upper))
+            )?,
+            None => writeln!(output, "  simulated win rate: -")?,
+        }
+        if let Some(exact_win_rate) = &host_report.exact_win_rate {
+            writeln!(
+                output,
+                "  exact win rate: {} ({})",
+                exact_win_rate,
+                format_percentage(exact_win_rate.to_f64())
+            )?;
+        }
+        if let Some(absolute_error) = host_report.absolute_error() {
+            writeln!(output, "  absolute error: {:.6}", absolute_error)?;
+        }
+        writeln!(
+            output,
+            "  win rate when a switch was offered: {} ({} games)",
+            format_percentage(statistics.win_rate_when_offered()),
+            statistics.offer_count
+        )?;
+        if statistics.revealed_count > 0 {
+            writeln!(
+                output,
+                "  a prize was revealed in {} games",
+                statistics.revealed_count
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn create_json(report: &Report) -> Value {
+    let results: Vec<Value> = report
+        .host_reports
+        .iter()
+        .map(|host_report| {
+            let statistics = &host_report.statistics;
+            json!({
+                "host": host_report.host,
+                "games": statistics.game_count,
+                "wins": statistics.win_count,
+                "win_rate": statistics.win_rate(),
+                "confidence_interval": statistics.win_rate_confidence_interval(),
+                "exact_win_rate": host_report.exact_win_rate.as_ref().map(ToString::to_string),
+                "exact_win_rate_value": host_report.exact_win_rate.as_ref().and_then(ToPrimitive::to_f64),
+                "absolute_error": host_report.absolute_error(),
+                "offered_games": statistics.offer_count,
+                "offered_win_rate": statistics.win_rate_when_offered(),
+                "revealed_games": statistics.revealed_count,
+            })
+        })
+        .collect();
+    json!({
+        "seed": report.seed,
+        "doors": report.config.door_count(),
+        "opened_doors": report.config.opened_door_count(),
+        "prizes": report.config.prize_count(),
+        "strategy": report.strategy,
+        "results": results,
+    })
+}
+
+fn write_csv_rows(report: &Report, output: &mut dyn Write) -> io::Result<()> {
+    for host_report in &report.host_reports {
+        let statistics = &host_report.statistics;
+        let confidence_interval = statistics.win_rate_confidence_interval();
+        writeln!(
+            output,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            host_report.host,
+            report.strategy,
+            report.config.door_count(),
+            report.config.opened_door_count(),
+            report.config.prize_count(),
+            statistics.game_count,
+            statistics.win_count,
+            format_csv_value(statistics.win_rate()),
+            format_csv_value(confidence_interval.map(|(lower, _)| lower)),
+            format_csv_value(confidence_interval.map(|(_, upper)| upper)),
+            format_csv_value(host_report.exact_win_rate.as_ref()),
+            format_csv_value(
+                host_report
+                    .exact_win_rate
+                    .as_ref()
+                    .and_then(ToPrimitive::to_f64)
+            ),
+            format_csv_value(host_report.absolute_error()),
+            statistics.offer_count,
+            format_csv_value(statistics.win_rate_when_offered()),
+            statistics.revealed_count
+        )?;
+    }
+    Ok(())
+}
+
+// The text reports are separated by empty lines, the JSON reports are written as JSON Lines and
+// the CSV has a single header
+pub fn write_reports(reports: &[Report], format: Format, output: &mut dyn Write) -> io::Result<()> {
+    if format == Format::Csv {
+        writeln!(output, "{}", CSV_HEADER)?;
+    }
+    for (index, report) in reports.iter().enumerate() {
+        match format {
+            Format::Text => {
+                if index > 0 {
+                    writeln!(output)?;
+                }
+                write_text(report, output)?;
+            }
+            Format::Json => writeln!(output, "{}", create_json(report))?,
+            Format::Csv => write_csv_rows(report, output)?,
+        }
+    }
+    Ok(())
+}
+
+pub fn write_report(report: &Report, format: Format, output: &mut dyn Write) -> io::Result<()> {
+    write_reports(slice::from_ref(report), format, output)
+}
+
+fn format_prize_count(count: usize) -> String {
+    if count == 1 {
+        "1 prize".to_string()
+    } else {
+        format!("{} prizes", count)
+    }
+}
+
+fn format_host_move(host_move: HostMove) -> String {
+    match host_move {
+        HostMove::NoOffer => "no offer".to_string(),
+        HostMove::OfferSwitch {
+            revealed_prize_count,
+        } => format!(
+            "offers a switch revealing {}",
+            format_prize_count(revealed_prize_count)
+        ),
+    }
+}
+
+fn write_probability(
+    output: &mut dyn Write,
+    label: &str,
+    probability: &BigRational,
+) -> io::Result<()> {
+    writeln!(
+        output,
+        "  {}: {} ({})",
+        label,
+        probability,
+        format_percentage(probability.to_f64())
+    )
+}
+
+// The equilibrium strategies with the exact win rates of the pure strategies against the host
+pub fn write_equilibrium(equilibrium: &Equilibrium, output: &mut dyn Write) -> io::Result<()> {
+    let config = equilibrium.game().config();
+    writeln!(
+        output,
+        "doors: {}, opened doors: {}, prizes: {}",
+        config.door_count(),
+        config.opened_door_count(),
+        config.prize_count()
+    )?;
+    writeln!(
+        output,
+        "value of the game: {} ({})",
+        equilibrium.value(),
+        format_percentage(equilibrium.value().to_f64())
+    )?;
+    writeln!(output, "contestant switches after the host revealed:")?;
+    for (revealed_prize_count, probability) in equilibrium.switch_probabilities() {
+        write_probability(
+            output,
+            &format_prize_count(*revealed_prize_count),
+            probability,
+        )?;
+    }
+    for (first_choice_won, label) in [(true, "winning"), (false, "losing")] {
+        writeln!(output, "host after a {} first choice:", label)?;
+        for (host_move, probability) in equilibrium.host_strategy(first_choice_won) {
+            write_probability(output, &format_host_move(*host_move), probability)?;
+        }
+    }
+    let host = equilibrium.host();
+    writeln!(output, "against the equilibrium host:")?;
+    for strategy in &[&AlwaysStay as &dyn Strategy, &AlwaysSwitch] {
+        if let Some(win_rate) = exact_win_probability(&config, &host, *strategy) {
+            write_probability(output, &strategy.name(), &win_rate)?;
+        }
+    }
+    Ok(())
+}
+
+// Returns the item which fulfills the condition, a new item is created when none of them does
+pub fn find_or_insert<T>(
+    items: &mut Vec<T>,
+    is_searched: impl Fn(&T) -> bool,
+    create: impl FnOnce() -> T,
+) -> &mut T {
+    let index = match items.iter().position(is_searched) {
+        Some(index) => index,
+        None => {
+            items.push(create());
+            items.len() - 1
+        }
+    };
+    &mut items[index]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use monty_hall::{ratio, ZeroSumGame};
+
+    fn create_report() -> Report {
+        Report {
+            config: GameConfig::classic(3).unwrap(),
+            strategy: "switch".to_string(),
+            seed: 42,
+            host_reports: vec![
+                HostReport {
+                    host: "standard".to_string(),
+                    statistics: Statistics {
+                        game_count: 4,
+                        win_count: 2,
+                        offer_count: 4,
+                        offer_win_count: 2,
+                        revealed_count: 0,
+                    },
+                    exact_win_rate: Some(ratio(2, 3)),
+                },
+                HostReport {
+                    host: "custom".to_string(),
+                    statistics: Statistics::new(),
+                    exact_win_rate: None,
+                },
+            ],
+        }
+    }
+
+    fn write_to_string(format: Format) -> String {
+        let mut output = Vec::new();
+        write_report(&create_report(), format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn text_report() {
+        let text = write_to_string(Format::Text);
+        assert!(text.starts_with("seed: 42\nstandard host:\n  the user won 2 times in 4 games\n"));
+        assert!(text.contains("  exact win rate: 2/3 (66.67%)\n"));
+        assert!(text.contains("  absolute error: 0.166667\n"));
+        assert!(text.contains(
+            "custom host:\n  the user won 0 times in 0 games\n  simulated win rate: -\n"
+        ));
+    }
+
+    #[test]
+    fn json_report() {
+        let report: Value = serde_json::from_str(&write_to_string(Format::Json)).unwrap();
+        assert_eq!(json!(42), report["seed"]);
+        assert_eq!(json!(1), report["opened_doors"]);
+        assert_eq!(json!("switch"), report["strategy"]);
+        assert_eq!(json!("2/3"), report["results"][0]["exact_win_rate"]);
+        assert_eq!(json!(0.5), report["results"][0]["win_rate"]);
+        assert_eq!(Value::Null, report["results"][1]["win_rate"]);
+        assert_eq!(Value::Null, report["results"][1]["exact_win_rate"]);
+    }
+
+    #[test]
+    fn csv_report() {
+        let csv = write_to_string(Format::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(3, lines.len());
+        assert_eq!(CSV_HEADER, lines[0]);
+        let header_count = CSV_HEADER.split(',').count();
+        assert!(lines
+            .iter()
+            .all(|line| line.split(',').count() == header_count));
+        assert!(lines[1].starts_with("standard,switch,3,1,1,4,2,0.5,"));
+        assert!(lines[1].contains(",2/3,"));
+        assert_eq!("custom,switch,3,1,1,0,0,,,,,,,0,,0", lines[2]);
+    }
+
+    #[test]
+    fn multiple_reports() {
+        let reports = [create_report(), create_report()];
+        for (format, line_count) in &[(Format::Text, 23), (Format::Json, 2), (Format::Csv, 5)] {
+            let mut output = Vec::new();
+            write_reports(&reports, *format, &mut output).unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert_eq!(*line_count, output.lines().count(), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn equilibrium_report() {
+        let mut game = ZeroSumGame::new(GameConfig::classic(3).unwrap());
+        game.set_host_can_decline(false);
+        let mut output = Vec::new();
+        write_equilibrium(&game.solve(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("doors: 3, opened doors: 1, prizes: 1\n"));
+        assert!(output.contains("value of the game: 1/3 (33.33%)\n"));
+        assert!(output.contains("  1 prize: 0 (0.00%)\n"));
+        assert!(output.contains(
+            "host after a losing first choice:\n  offers a switch revealing 0 prizes: 1/2 (50.00%)\n"
+        ));
+        assert!(output.ends_with("  stay: 1/3 (33.33%)\n  switch: 1/3 (33.33%)\n"));
+    }
+}
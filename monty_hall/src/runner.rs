@@ -0,0 +1,215 @@
+use crate::config::GameConfig;
+use crate::door_game::DoorGame;
+use crate::host::Host;
+use crate::statistics::Statistics;
+use crate::strategy::Strategy;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+pub static INVALID_THREAD_COUNT_ERROR: &str = "At least 1 thread is necessary!";
+
+// The games are simulated in chunks and every chunk has its own random number generator, so the
+// results don't depend on which thread simulated which chunk
+pub const GAMES_PER_CHUNK: u64 = 1 << 16;
+
+pub fn create_chunk_rng(seed: u64, chunk_index: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    rng.set_stream(chunk_index);
+    rng
+}
+
+fn get_chunk_count(game_count: u64) -> u64 {
+    game_count.div_ceil(GAMES_PER_CHUNK)
+}
+
+fn get_chunk_game_count(game_count: u64, chunk_index: u64) -> u64 {
+    (game_count - chunk_index * GAMES_PER_CHUNK).min(GAMES_PER_CHUNK)
+}
+
+pub struct Simulation<'a> {
+    config: GameConfig,
+    host: &'a dyn Host,
+    strategy: &'a dyn Strategy,
+    seed: u64,
+    thread_count: usize,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(
+        config: GameConfig,
+        host: &'a dyn Host,
+        strategy: &'a dyn Strategy,
+        seed: u64,
+    ) -> Simulation<'a> {
+        Simulation {
+            config,
+            host,
+            strategy,
+            seed,
+            thread_count: 1,
+        }
+    }
+
+    pub fn set_thread_count(&mut self, thread_count: usize) -> Result<(), &'static str> {
+        if thread_count == 0 {
+            return Err(INVALID_THREAD_COUNT_ERROR);
+        }
+        self.thread_count = thread_count;
+        Ok(())
+    }
+
+    pub fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    // Every game is played with a reset layout, so the generator used for the creation doesn't
+    // affect the results
+    fn create_game(&self) -> DoorGame {
+        DoorGame::new(self.config, &mut ChaCha8Rng::seed_from_u64(self.seed))
+    }
+
+    fn run_chunk(
+        &self,
+        game: &mut DoorGame,
+        game_count: u64,
+        chunk_index: u64,
+        statistics: &mut Statistics,
+        observer: &mut dyn FnMut(&DoorGame),
+    ) -> Result<(), &'static str> {
+        let mut rng = create_chunk_rng(self.seed, chunk_index);
+        for _ in 0..get_chunk_game_count(game_count, chunk_index) {
+            statistics.add(game.play(self.host, self.strategy, &mut rng)?);
+            observer(game);
+        }
+        Ok(())
+    }
+
+    // Plays the games in order on the current thread and calls the observer after every game
+    pub fn run_sequentially(
+        &self,
+        game_count: u64,
+        mut observer: impl FnMut(&DoorGame),
+    ) -> Result<Statistics, &'static str> {
+        let mut game = self.create_game();
+        let mut statistics = Statistics::new();
+        for chunk_index in 0..get_chunk_count(game_count) {
+            self.run_chunk(
+                &mut game,
+                game_count,
+                chunk_index,
+                &mut statistics,
+                &mut observer,
+            )?;
+        }
+        Ok(statistics)
+    }
+
+    // The statistics are the same as the ones of run_sequentially regardless of the number of
+    // threads
+    pub fn run(&self, game_count: u64) -> Result<Statistics, &'static str> {
+        if self.thread_count == 1 {
+            return self.run_sequentially(game_count, |_| ());
+        }
+        let chunk_count = get_chunk_count(game_count);
+        let next_chunk_index = AtomicU64::new(0);
+        let worker_results: Vec<Result<Statistics, &'static str>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.thread_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut game = self.create_game();
+                        let mut statistics = Statistics::new();
+                        loop {
+                            let chunk_index = next_chunk_index.fetch_add(1, Ordering::Relaxed);
+                            if chunk_index >= chunk_count {
+                                return Ok(statistics);
+                            }
+                            self.run_chunk(
+                                &mut game,
+                                game_count,
+                                chunk_index,
+                                &mut statistics,
+                                &mut |_| (),
+                            )?;
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("The simulation thread panicked!"))
+                .collect()
+        });
+
+        let mut statistics = Statistics::new();
+        for worker_result in worker_results {
+            statistics.merge(&worker_result?);
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::host::{MontyFall, StandardHost};
+    use crate::strategy::{AlwaysSwitch, RandomSwitch};
+
+    #[test]
+    fn chunks() {
+        assert_eq!(0, get_chunk_count(0));
+        assert_eq!(1, get_chunk_count(1));
+        assert_eq!(1, get_chunk_count(GAMES_PER_CHUNK));
+        assert_eq!(2, get_chunk_count(GAMES_PER_CHUNK + 1));
+        assert_eq!(
+            GAMES_PER_CHUNK,
+            get_chunk_game_count(GAMES_PER_CHUNK + 1, 0)
+        );
+        assert_eq!(1, get_chunk_game_count(GAMES_PER_CHUNK + 1, 1));
+    }
+
+    #[test]
+    fn invalid_thread_count() {
+        let config = GameConfig::classic(3).unwrap();
+        let mut simulation = Simulation::new(config, &StandardHost, &AlwaysSwitch, 0);
+        assert_eq!(
+            Err(INVALID_THREAD_COUNT_ERROR),
+            simulation.set_thread_count(0)
+        );
+        assert_eq!(1, simulation.thread_count());
+    }
+
+    #[test]
+    fn same_results_regardless_of_thread_count() {
+        let config = GameConfig::new(5, 2, 2).unwrap();
+        let game_count = 2 * GAMES_PER_CHUNK + 123;
+        let mut simulation = Simulation::new(config, &MontyFall, &RandomSwitch, 42);
+        let statistics = simulation.run(game_count).unwrap();
+        assert_eq!(game_count, statistics.game_count);
+        for thread_count in &[2, 3, 8] {
+            simulation.set_thread_count(*thread_count).unwrap();
+            assert_eq!(statistics, simulation.run(game_count).unwrap());
+        }
+
+        let other_simulation = Simulation::new(config, &MontyFall, &RandomSwitch, 43);
+        assert_ne!(statistics, other_simulation.run(game_count).unwrap());
+    }
+
+    #[test]
+    fn observe_every_game() {
+        let config = GameConfig::classic(3).unwrap();
+        let simulation = Simulation::new(config, &StandardHost, &AlwaysSwitch, 7);
+        let mut win_count = 0;
+        let statistics = simulation
+            .run_sequentially(1000, |game| {
+                if game.is_user_won() {
+                    win_count += 1;
+                }
+            })
+            .unwrap();
+        assert_eq!(1000, statistics.game_count);
+        assert_eq!(win_count, statistics.win_count);
+        assert_eq!(statistics, simulation.run(1000).unwrap());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::door_game::GameResult;
+
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Statistics {
+    pub game_count: u64,
+    pub win_count: u64,
+    // The games where the contestant could switch and the jackpot was still hidden
+    pub offer_count: u64,
+    pub offer_win_count: u64,
+    pub revealed_count: u64,
+}
+
+// The quantile of the standard normal distribution for 95% confidence
+const Z_95: f64 = 1.959_963_984_540_054;
+
+fn get_rate(count: u64, total: u64) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some(count as f64 / total as f64)
+    }
+}
+
+// The Wilson score interval, which behaves well even if the rate is close to 0 or 1
+fn get_confidence_interval(count: u64, total: u64, z: f64) -> Option<(f64, f64)> {
+    let rate = get_rate(count, total)?;
+    let total = total as f64;
+    let z_squared = z * z;
+    let denominator = 1.0 + z_squared / total;
+    let center = (rate + z_squared / (2.0 * total)) / denominator;
+    let half_width =
+        z * (rate * (1.0 - rate) / total + z_squared / (4.0 * total * total)).sqrt() / denominator;
+    Some((
+        (center - half_width).max(0.0),
+        (center + half_width).min(1.0),
+    ))
+}
+
+impl Statistics {
+    pub fn new() -> Statistics {
+        Statistics::default()
+    }
+
+    pub fn add(&mut self, result: GameResult) {
+        self.game_count += 1;
+        if result.won {
+            self.win_count += 1;
+        }
+        if result.prize_revealed {
+            self.revealed_count += 1;
+        } else if result.switch_offered {
+            self.offer_count += 1;
+            if result.won {
+                self.offer_win_count += 1;
+            }
+        }
+    }
+
+    pub fn merge(&mut self, other: &Statistics) {
+        self.game_count += other.game_count;
+        self.win_count += other.win_count;
+        self.offer_count += other.offer_count;
+        self.offer_win_count += other.offer_win_count;
+        self.revealed_count += other.revealed_count;
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        get_rate(self.win_count, self.game_count)
+    }
+
+    // The 95% confidence interval of the win rate
+    pub fn win_rate_confidence_interval(&self) -> Option<(f64, f64)> {
+        get_confidence_interval(self.win_count, self.game_count, Z_95)
+    }
+
+    // The conditional win rate which is taught by the different host models
+    pub fn win_rate_when_offered(&self) -> Option<f64> {
+        get_rate(self.offer_win_count, self.offer_count)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_result(won: bool, switch_offered: bool, prize_revealed: bool) -> GameResult {
+        GameResult {
+            won,
+            switch_offered,
+            prize_revealed,
+        }
+    }
+
+    #[test]
+    fn empty_statistics() {
+        let statistics = Statistics::new();
+        assert_eq!(None, statistics.win_rate());
+        assert_eq!(None, statistics.win_rate_when_offered());
+        assert_eq!(None, statistics.win_rate_confidence_interval());
+    }
+
+    #[test]
+    fn confidence_interval() {
+        let statistics = Statistics {
+            game_count: 1000,
+            win_count: 500,
+            ..Statistics::new()
+        };
+        let (lower, upper) = statistics.win_rate_confidence_interval().unwrap();
+        assert!((0.469 - lower).abs() < 1e-3);
+        assert!((0.531 - upper).abs() < 1e-3);
+
+        let statistics = Statistics {
+            game_count: 10,
+            win_count: 10,
+            ..Statistics::new()
+        };
+        let (lower, upper) = statistics.win_rate_confidence_interval().unwrap();
+        assert!(0.6 < lower && lower < 0.75);
+        assert!((1.0 - upper).abs() < 1e-12);
+    }
+
+    #[test]
+    fn add_and_merge() {
+        let mut statistics = Statistics::new();
+        statistics.add(create_result(true, true, false));
+        statistics.add(create_result(false, true, false));
+        statistics.add(create_result(true, false, false));
+        statistics.add(create_result(false, true, true));
+        assert_eq!(
+            Statistics {
+                game_count: 4,
+                win_count: 2,
+                offer_count: 2,
+                offer_win_count: 1,
+                revealed_count: 1,
+            },
+            statistics
+        );
+        assert_eq!(Some(0.5), statistics.win_rate());
+        assert_eq!(Some(0.5), statistics.win_rate_when_offered());
+
+        let mut merged = statistics;
+        merged.merge(&statistics);
+        assert_eq!(8, merged.game_count);
+        assert_eq!(2, merged.revealed_count);
+        assert_eq!(statistics.win_rate(), merged.win_rate());
+    }
+}
@@ -0,0 +1,218 @@
+use rand::seq::IteratorRandom;
+use rand::{Rng, RngCore};
+
+pub static INVALID_STRATEGY_ERROR: &str = "Invalid strategy!";
+
+// What the contestant knows when the host has opened the doors
+pub struct Observation<'a> {
+    pub door_count: usize,
+    pub chosen_door: usize,
+    pub opened_doors: &'a [usize],
+    // The number of opened doors which hide a prize
+    pub revealed_prize_count: usize,
+}
+
+impl<'a> Observation<'a> {
+    // The closed doors which are not chosen by the contestant
+    pub fn other_closed_doors(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.door_count)
+            .filter(move |door| *door != self.chosen_door && !self.opened_doors.contains(door))
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Decision {
+    Stay,
+    SwitchTo(usize),
+}
+
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> String;
+    fn decide(&self, observation: &Observation, rng: &mut dyn RngCore) -> Decision;
+
+    // The probability of switching to a uniformly chosen closed door regardless of the
+    // observation, None if the strategy behaves differently
+    fn switch_probability(&self) -> Option<f64> {
+        None
+    }
+}
+
+pub(crate) fn switch_to_random_door(observation: &Observation, rng: &mut dyn RngCore) -> Decision {
+    match observation.other_closed_doors().choose(rng) {
+        Some(door) => Decision::SwitchTo(door),
+        None => Decision::Stay,
+    }
+}
+
+pub struct AlwaysStay;
+
+impl Strategy for AlwaysStay {
+    fn name(&self) -> String {
+        "stay".to_string()
+    }
+
+    fn switch_probability(&self) -> Option<f64> {
+        Some(0.0)
+    }
+
+    fn decide(&self, _observation: &Observation, _rng: &mut dyn RngCore) -> Decision {
+        Decision::Stay
+    }
+}
+
+pub struct AlwaysSwitch;
+
+impl Strategy for AlwaysSwitch {
+    fn name(&self) -> String {
+        "switch".to_string()
+    }
+
+    fn switch_probability(&self) -> Option<f64> {
+        Some(1.0)
+    }
+
+    fn decide(&self, observation: &Observation, rng: &mut dyn RngCore) -> Decision {
+        switch_to_random_door(observation, rng)
+    }
+}
+
+// Flips a fair coin to decide
+pub struct RandomSwitch;
+
+impl Strategy for RandomSwitch {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn switch_probability(&self) -> Option<f64> {
+        Some(0.5)
+    }
+
+    fn decide(&self, observation: &Observation, rng: &mut dyn RngCore) -> Decision {
+        if rng.gen_bool(0.5) {
+            switch_to_random_door(observation, rng)
+        } else {
+            Decision::Stay
+        }
+    }
+}
+
+pub struct SwitchWithProbability {
+    probability: f64,
+}
+
+impl SwitchWithProbability {
+    pub fn new(probability: f64) -> Result<SwitchWithProbability, &'static str> {
+        if !(0.0..=1.0).contains(&probability) {
+            return Err(INVALID_STRATEGY_ERROR);
+        }
+        Ok(SwitchWithProbability { probability })
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+}
+
+impl Strategy for SwitchWithProbability {
+    fn name(&self) -> String {
+        format!("p={}", self.probability)
+    }
+
+    fn switch_probability(&self) -> Option<f64> {
+        Some(self.probability)
+    }
+
+    fn decide(&self, observation: &Observation, rng: &mut dyn RngCore) -> Decision {
+        if rng.gen_bool(self.probability) {
+            switch_to_random_door(observation, rng)
+        } else {
+            Decision::Stay
+        }
+    }
+}
+
+// Accepted names: stay, switch, random and p=<probability>
+pub fn parse_strategy(name: &str) -> Result<Box<dyn Strategy>, &'static str> {
+    match name {
+        "stay" => Ok(Box::new(AlwaysStay)),
+        "switch" => Ok(Box::new(AlwaysSwitch)),
+        "random" => Ok(Box::new(RandomSwitch)),
+        _ => {
+            let probability = name
+                .strip_prefix("p=")
+                .and_then(|probability| probability.parse::<f64>().ok())
+                .ok_or(INVALID_STRATEGY_ERROR)?;
+            Ok(Box::new(SwitchWithProbability::new(probability)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn count_switches(strategy: &dyn Strategy, count: usize) -> usize {
+        let mut rng = StdRng::seed_from_u64(1);
+        let opened_doors = [1, 3];
+        let observation = Observation {
+            door_count: 4,
+            chosen_door: 0,
+            opened_doors: &opened_doors,
+            revealed_prize_count: 0,
+        };
+        (0..count)
+            .filter(|_| match strategy.decide(&observation, &mut rng) {
+                Decision::Stay => false,
+                Decision::SwitchTo(door) => {
+                    assert_eq!(2, door);
+                    true
+                }
+            })
+            .count()
+    }
+
+    #[test]
+    fn other_closed_doors() {
+        let opened_doors = [1, 4];
+        let observation = Observation {
+            door_count: 5,
+            chosen_door: 2,
+            opened_doors: &opened_doors,
+            revealed_prize_count: 0,
+        };
+        assert_eq!(
+            vec![0, 3],
+            observation.other_closed_doors().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn built_in_strategies() {
+        assert_eq!(0, count_switches(&AlwaysStay, 100));
+        assert_eq!(100, count_switches(&AlwaysSwitch, 100));
+        let random_switches = count_switches(&RandomSwitch, 1000);
+        assert!(400 < random_switches && random_switches < 600);
+        let rare_switches = count_switches(&SwitchWithProbability::new(0.1).unwrap(), 1000);
+        assert!(50 < rare_switches && rare_switches < 150);
+    }
+
+    #[test]
+    fn invalid_probability() {
+        assert!(SwitchWithProbability::new(-0.1).is_err());
+        assert!(SwitchWithProbability::new(1.5).is_err());
+        assert!(SwitchWithProbability::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn parse_strategies() {
+        for name in &["stay", "switch", "random", "p=0.25"] {
+            assert_eq!(*name, parse_strategy(name).unwrap().name());
+        }
+        assert!(parse_strategy("sometimes").is_err());
+        assert!(parse_strategy("p=2").is_err());
+        assert!(parse_strategy("p=x").is_err());
+    }
+}
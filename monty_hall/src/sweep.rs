@@ -0,0 +1,176 @@
+// Simulates every combination of door counts, strategies and hosts
+use crate::chart::{render_svg, Panel, Point, Series};
+use crate::report::{find_or_insert, HostReport, Report};
+use monty_hall::{GameConfig, Host, Simulation, Strategy};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+pub static EMPTY_DOOR_RANGE_ERROR: &str = "The range of door counts is empty!";
+
+pub struct Sweep {
+    pub door_counts: RangeInclusive<usize>,
+    // The host opens all but two doors when it is not given
+    pub opened_door_count: Option<usize>,
+    pub prize_count: usize,
+    pub strategies: Vec<Arc<dyn Strategy>>,
+    pub hosts: Vec<Arc<dyn Host>>,
+    pub game_count: u64,
+    pub seed: u64,
+    pub thread_count: usize,
+}
+
+impl Sweep {
+    // Every configuration is validated before the first simulation is started
+    fn create_configs(&self) -> Result<Vec<GameConfig>, &'static str> {
+        if self.door_counts.is_empty() {
+            return Err(EMPTY_DOOR_RANGE_ERROR);
+        }
+        self.door_counts
+            .clone()
+            .map(|door_count| {
+                let opened_door_count = self
+                    .opened_door_count
+                    .unwrap_or_else(|| door_count.saturating_sub(2));
+                GameConfig::new(door_count, opened_door_count, self.prize_count)
+            })
+            .collect()
+    }
+
+    // Returns a report for every door count and strategy, in this order
+    pub fn run(&self) -> Result<Vec<Report>, &'static str> {
+        let mut reports = Vec::new();
+        for config in self.create_configs()? {
+            for strategy in &self.strategies {
+                let host_reports = self
+                    .hosts
+                    .iter()
+                    .map(|host| {
+                        let mut simulation =
+                            Simulation::new(config, host.as_ref(), strategy.as_ref(), self.seed);
+                        simulation.set_thread_count(self.thread_count)?;
+                        let statistics = simulation.run(self.game_count)?;
+                        Ok(HostReport::new(
+                            &config,
+                            host.as_ref(),
+                            strategy.as_ref(),
+                            statistics,
+                        ))
+                    })
+                    .collect::<Result<Vec<HostReport>, &'static str>>()?;
+                reports.push(Report {
+                    config,
+                    strategy: strategy.name(),
+                    seed: self.seed,
+                    host_reports,
+                });
+            }
+        }
+        Ok(reports)
+    }
+}
+
+// A panel for every host with a line for every strategy
+pub fn create_chart(reports: &[Report]) -> String {
+    let mut panels: Vec<Panel> = Vec::new();
+    for report in reports {
+        for host_report in &report.host_reports {
+            let (lower, upper) = match host_report.statistics.win_rate_confidence_interval() {
+                Some(confidence_interval) => confidence_interval,
+                None => continue,
+            };
+            let panel = find_or_insert(
+                &mut panels,
+                |panel| panel.title == host_report.host,
+                || Panel {
+                    title: host_report.host.clone(),
+                    series: Vec::new(),
+                },
+            );
+            let series = find_or_insert(
+                &mut panel.series,
+                |series| series.name == report.strategy,
+                || Series {
+                    name: report.strategy.clone(),
+                    points: Vec::new(),
+                },
+            );
+            series.points.push(Point {
+                x: report.config.door_count(),
+                win_rate: host_report.statistics.win_rate().unwrap_or_default(),
+                lower,
+                upper,
+            });
+        }
+    }
+    render_svg("Win rate by the number of doors", &panels)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::report::{write_reports, Format};
+    use monty_hall::{AlwaysStay, AlwaysSwitch, MontyFall, StandardHost};
+
+    fn create_sweep(door_counts: RangeInclusive<usize>) -> Sweep {
+        Sweep {
+            door_counts,
+            opened_door_count: None,
+            prize_count: 1,
+            strategies: vec![Arc::new(AlwaysStay), Arc::new(AlwaysSwitch)],
+            hosts: vec![Arc::new(StandardHost), Arc::new(MontyFall)],
+            game_count: 1000,
+            seed: 3,
+            thread_count: 1,
+        }
+    }
+
+    #[test]
+    fn invalid_sweeps() {
+        #[allow(clippy::reversed_empty_ranges)]
+        let sweep = create_sweep(5..=4);
+        assert_eq!(Err(EMPTY_DOOR_RANGE_ERROR), sweep.run().map(|_| ()));
+
+        let mut sweep = create_sweep(3..=6);
+        sweep.opened_door_count = Some(2);
+        assert!(sweep.run().is_err());
+    }
+
+    #[test]
+    fn run_every_combination() {
+        let sweep = create_sweep(3..=5);
+        let reports = sweep.run().unwrap();
+        assert_eq!(6, reports.len());
+        assert_eq!(3, reports[0].config.door_count());
+        assert_eq!("stay", reports[0].strategy);
+        assert_eq!(1, reports[0].config.opened_door_count());
+        assert_eq!(5, reports[5].config.door_count());
+        assert_eq!("switch", reports[5].strategy);
+        assert_eq!(3, reports[5].config.opened_door_count());
+        for report in &reports {
+            assert_eq!(2, report.host_reports.len());
+            for host_report in &report.host_reports {
+                assert_eq!(1000, host_report.statistics.game_count);
+            }
+        }
+
+        let mut csv = Vec::new();
+        write_reports(&reports, Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(13, csv.lines().count());
+        assert!(csv
+            .lines()
+            .nth(12)
+            .unwrap()
+            .starts_with("fall,switch,5,3,1,"));
+    }
+
+    #[test]
+    fn chart_of_sweep() {
+        let reports = create_sweep(3..=4).run().unwrap();
+        let svg = create_chart(&reports);
+        assert!(svg.contains(">standard</text>"));
+        assert!(svg.contains(">fall</text>"));
+        assert_eq!(2, svg.matches(">switch</text>").count());
+        assert_eq!(8, svg.matches("<circle").count());
+    }
+}
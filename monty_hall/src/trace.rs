@@ -0,0 +1,544 @@
+// Records every simulated game as a line of JSON and recomputes the reports from the records
+use crate::report::{find_or_insert, HostReport, Report};
+use monty_hall::{
+    exact_win_probability, parse_host, parse_strategy, Decision, DoorGame, GameConfig, GameResult,
+    Host, HostAction, Statistics,
+};
+use num_rational::BigRational;
+use rand::rngs::mock::StepRng;
+use rand::RngCore;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+pub static INVALID_TRACE_RECORD_ERROR: &str = "Invalid trace record!";
+pub static INCONSISTENT_OUTCOME_ERROR: &str = "The recorded outcome doesn't match the doors!";
+pub static PRIZE_REVEALED_ERROR: &str = "The recorded host can't reveal a prize!";
+pub static UNEXPECTED_OFFER_ERROR: &str =
+    "The switch offer doesn't follow the rule of the recorded host!";
+pub static UNEXPECTED_OPENED_DOORS_ERROR: &str =
+    "The opened doors don't follow the rule of the recorded host!";
+pub static UNEXPECTED_DECISION_ERROR: &str =
+    "The final choice doesn't follow the recorded strategy!";
+
+fn create_record(game: &DoorGame, host: &str, strategy: &str, seed: u64, game_index: u64) -> Value {
+    let config = game.config();
+    let prize_doors: Vec<usize> = (0..game.door_count())
+        .filter(|door| game.has_prize(*door))
+        .collect();
+    json!({
+        "host": host,
+        "strategy": strategy,
+        "seed": seed,
+        "game": game_index,
+        "doors": config.door_count(),
+        "opened_door_count": config.opened_door_count(),
+        "prize_doors": prize_doors,
+        "initial_door": game.initial_door(),
+        "opened_doors": game.opened_doors(),
+        "switch_offered": game.is_switch_offered(),
+        "final_door": game.chosen_door(),
+        "won": game.is_user_won(),
+    })
+}
+
+// The errors are kept until the tracer is finished, so it can be used as the observer of a
+// simulation
+pub struct Tracer<W: Write> {
+    output: W,
+    strategy: String,
+    seed: u64,
+    host: String,
+    game_index: u64,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Tracer<W> {
+    pub fn new(output: W, strategy: String, seed: u64) -> Tracer<W> {
+        Tracer {
+            output,
+            strategy,
+            seed,
+            host: String::new(),
+            game_index: 0,
+            error: None,
+        }
+    }
+
+    // The games of every host are numbered from zero
+    pub fn set_host(&mut self, host: String) {
+        self.host = host;
+        self.game_index = 0;
+    }
+
+    pub fn trace(&mut self, game: &DoorGame) {
+        if self.error.is_some() {
+            return;
+        }
+        let record = create_record(game, &self.host, &self.strategy, self.seed, self.game_index);
+        self.game_index += 1;
+        if let Err(error) = writeln!(self.output, "{}", record) {
+            self.error = Some(error);
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.output.flush(),
+        }
+    }
+}
+
+struct TraceRecord {
+    host: String,
+    strategy: String,
+    seed: u64,
+    config: GameConfig,
+    prize_doors: Vec<usize>,
+    initial_door: usize,
+    opened_doors: Vec<usize>,
+    switch_offered: bool,
+    final_door: usize,
+    won: bool,
+}
+
+fn get_string(record: &Value, key: &str) -> Result<String, &'static str> {
+    record[key]
+        .as_str()
+        .map(ToString::to_string)
+        .ok_or(INVALID_TRACE_RECORD_ERROR)
+}
+
+fn get_number(record: &Value, key: &str) -> Result<usize, &'static str> {
+    record[key]
+        .as_u64()
+        .map(|number| number as usize)
+        .ok_or(INVALID_TRACE_RECORD_ERROR)
+}
+
+fn get_doors(record: &Value, key: &str) -> Result<Vec<usize>, &'static str> {
+    record[key]
+        .as_array()
+        .ok_or(INVALID_TRACE_RECORD_ERROR)?
+        .iter()
+        .map(|door| {
+            door.as_u64()
+                .map(|door| door as usize)
+                .ok_or(INVALID_TRACE_RECORD_ERROR)
+        })
+        .collect()
+}
+
+fn get_bool(record: &Value, key: &str) -> Result<bool, &'static str> {
+    record[key].as_bool().ok_or(INVALID_TRACE_RECORD_ERROR)
+}
+
+impl TraceRecord {
+    fn parse(line: &str) -> Result<TraceRecord, &'static str> {
+        let record: Value = serde_json::from_str(line).map_err(|_| INVALID_TRACE_RECORD_ERROR)?;
+        let prize_doors = get_doors(&record, "prize_doors")?;
+        let config = GameConfig::new(
+            get_number(&record, "doors")?,
+            get_number(&record, "opened_door_count")?,
+            prize_doors.len(),
+        )?;
+        Ok(TraceRecord {
+            host: get_string(&record, "host")?,
+            strategy: get_string(&record, "strategy")?,
+            seed: record["seed"].as_u64().ok_or(INVALID_TRACE_RECORD_ERROR)?,
+            config,
+            prize_doors,
+            initial_door: get_number(&record, "initial_door")?,
+            opened_doors: get_doors(&record, "opened_doors")?,
+            switch_offered: get_bool(&record, "switch_offered")?,
+            final_door: get_number(&record, "final_door")?,
+            won: get_bool(&record, "won")?,
+        })
+    }
+
+    // Checks the recorded action against the rules of the host if it is a built-in one. Whether
+    // the built-in hosts offer a switch depends only on the layout, so it is decided by letting
+    // the host act in the same game.
+    fn check_host_rules(&self, game: &DoorGame) -> Result<(), &'static str> {
+        let host = match parse_host(&self.host) {
+            Ok(host) => host,
+            Err(_) => return Ok(()),
+        };
+        if game.is_prize_revealed() && !host.may_reveal_prizes() {
+            return Err(PRIZE_REVEALED_ERROR);
+        }
+        let layout = DoorGame::with_layout(self.config, self.initial_door, &self.prize_doors)?;
+        let mut doors_to_open = Vec::new();
+        let action = host.act(&layout, &mut StepRng::new(0, 0), &mut doors_to_open);
+        if (action == HostAction::OfferSwitch) != self.switch_offered {
+            return Err(UNEXPECTED_OFFER_ERROR);
+        }
+        if self.switch_offered && host.opens_fixed_doors() {
+            let mut opened_doors = self.opened_doors.clone();
+            opened_doors.sort_unstable();
+            doors_to_open.sort_unstable();
+            if opened_doors != doors_to_open {
+                return Err(UNEXPECTED_OPENED_DOORS_ERROR);
+            }
+        }
+        Ok(())
+    }
+
+    // Checks the final choice against the built-in strategies: the ones which always stay or
+    // always switch can't make the other decision after an offer
+    fn check_strategy_rules(&self) -> Result<(), &'static str> {
+        let switch_probability = match parse_strategy(&self.strategy) {
+            Ok(strategy) => strategy.switch_probability(),
+            Err(_) => return Ok(()),
+        };
+        let switched = self.final_door != self.initial_door;
+        match switch_probability {
+            Some(probability) if probability == 0.0 && switched => Err(UNEXPECTED_DECISION_ERROR),
+            Some(probability) if probability == 1.0 && self.switch_offered && !switched => {
+                Err(UNEXPECTED_DECISION_ERROR)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Replays the game, so the doors are validated by the same rules as in the simulation
+    fn replay(&self) -> Result<GameResult, &'static str> {
+        let mut game = DoorGame::with_layout(self.config, self.initial_door, &self.prize_doors)?;
+        // The replay doesn't use random numbers
+        let switch_offered = game.open_doors(&ReplayHost(self), &mut StepRng::new(0, 0))?;
+        if switch_offered != self.switch_offered {
+            return Err(INVALID_TRACE_RECORD_ERROR);
+        }
+        self.check_host_rules(&game)?;
+        if self.final_door != self.initial_door {
+            game.apply_decision(Decision::SwitchTo(self.final_door))?;
+        }
+        self.check_strategy_rules()?;
+        let result = game.result();
+        if result.won != self.won {
+            return Err(INCONSISTENT_OUTCOME_ERROR);
+        }
+        Ok(result)
+    }
+}
+
+// Opens the recorded doors
+struct ReplayHost<'a>(&'a TraceRecord);
+
+impl Host for ReplayHost<'_> {
+    fn name(&self) -> String {
+        self.0.host.clone()
+    }
+
+    fn act(
+        &self,
+        _game: &DoorGame,
+        _rng: &mut dyn RngCore,
+        doors_to_open: &mut Vec<usize>,
+    ) -> HostAction {
+        if !self.0.switch_offered {
+            return HostAction::NoOffer;
+        }
+        doors_to_open.extend_from_slice(&self.0.opened_doors);
+        HostAction::OfferSwitch
+    }
+}
+
+// The exact win rate is known only for the built-in hosts and strategies
+fn get_exact_win_rate(record: &TraceRecord) -> Option<BigRational> {
+    let host = parse_host(&record.host).ok()?;
+    let strategy = parse_strategy(&record.strategy).ok()?;
+    exact_win_probability(&record.config, host.as_ref(), strategy.as_ref())
+}
+
+// Groups the games by the configuration, strategy and seed, then by the host. The empty lines are
+// skipped and the errors contain the number of the invalid line.
+pub fn analyze(input: &mut dyn BufRead) -> Result<Vec<Report>, String> {
+    let mut reports: Vec<Report> = Vec::new();
+    for (line_index, line) in input.lines().enumerate() {
+        let line = line.map_err(|error| error.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (record, result) = TraceRecord::parse(&line)
+            .and_then(|record| record.replay().map(|result| (record, result)))
+            .map_err(|error| format!("Line {}: {}", line_index + 1, error))?;
+        let report = find_or_insert(
+            &mut reports,
+            |report| {
+                report.config == record.config
+                    && report.strategy == record.strategy
+                    && report.seed == record.seed
+            },
+            || Report {
+                config: record.config,
+                strategy: record.strategy.clone(),
+                seed: record.seed,
+                host_reports: Vec::new(),
+            },
+        );
+        let host_report = find_or_insert(
+            &mut report.host_reports,
+            |host_report| host_report.host == record.host,
+            || HostReport {
+                host: record.host.clone(),
+                statistics: Statistics::new(),
+                exact_win_rate: get_exact_win_rate(&record),
+            },
+        );
+        host_report.statistics.add(result);
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use monty_hall::{AngelicHost, MontyFall, Simulation, StandardHost, SwitchWithProbability};
+
+    fn trace(config: GameConfig, hosts: &[&dyn Host], game_count: u64) -> (Vec<Report>, String) {
+        let strategy = SwitchWithProbability::new(0.75).unwrap();
+        let mut tracer = Tracer::new(Vec::new(), "p=0.75".to_string(), 17);
+        let host_reports = hosts
+            .iter()
+            .map(|host| {
+                tracer.set_host(host.name());
+                let simulation = Simulation::new(config, *host, &strategy, 17);
+                let statistics = simulation
+                    .run_sequentially(game_count, |game| tracer.trace(game))
+                    .unwrap();
+                HostReport::new(&config, *host, &strategy, statistics)
+            })
+            .collect();
+        let trace = String::from_utf8(tracer.output).unwrap();
+        let report = Report {
+            config,
+            strategy: "p=0.75".to_string(),
+            seed: 17,
+            host_reports,
+        };
+        (vec![report], trace)
+    }
+
+    fn assert_same_reports(expected: &[Report], actual: &[Report]) {
+        assert_eq!(expected.len(), actual.len());
+        for (expected, actual) in expected.iter().zip(actual) {
+            assert_eq!(expected.config, actual.config);
+            assert_eq!(expected.strategy, actual.strategy);
+            assert_eq!(expected.seed, actual.seed);
+            assert_eq!(expected.host_reports.len(), actual.host_reports.len());
+            for (expected, actual) in expected.host_reports.iter().zip(&actual.host_reports) {
+                assert_eq!(expected.host, actual.host);
+                assert_eq!(expected.statistics, actual.statistics);
+                assert_eq!(expected.exact_win_rate, actual.exact_win_rate);
+            }
+        }
+    }
+
+    fn analyze_lines(lines: &[String]) -> Result<Vec<Report>, String> {
+        analyze(&mut lines.join("\n").as_bytes())
+    }
+
+    #[test]
+    fn record_of_a_game() {
+        let mut rng = StepRng::new(0, 0);
+        let config = GameConfig::classic(3).unwrap();
+        let mut game = DoorGame::with_layout(config, 1, &[2]).unwrap();
+        game.open_doors(&StandardHost, &mut rng).unwrap();
+        game.apply_decision(Decision::SwitchTo(2)).unwrap();
+        assert_eq!(
+            json!({
+                "host": "standard",
+                "strategy": "switch",
+                "seed": 5,
+                "game": 9,
+                "doors": 3,
+                "opened_door_count": 1,
+                "prize_doors": [2],
+                "initial_door": 1,
+                "opened_doors": [0],
+                "switch_offered": true,
+                "final_door": 2,
+                "won": true,
+            }),
+            create_record(&game, "standard", "switch", 5, 9)
+        );
+    }
+
+    #[test]
+    fn analyze_the_trace_of_a_simulation() {
+        let config = GameConfig::new(5, 2, 2).unwrap();
+        let (reports, trace) = trace(config, &[&StandardHost, &MontyFall, &AngelicHost], 2000);
+        assert_eq!(6000, trace.lines().count());
+        assert_same_reports(&reports, &analyze(&mut trace.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn group_the_records() {
+        let (mut reports, first_trace) = trace(GameConfig::classic(3).unwrap(), &[&MontyFall], 10);
+        let (other_reports, second_trace) =
+            trace(GameConfig::classic(4).unwrap(), &[&MontyFall], 5);
+        reports.extend(other_reports);
+        let trace = format!("{}\n{}", first_trace, second_trace);
+        assert_same_reports(&reports, &analyze(&mut trace.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn invalid_records() {
+        let (_, trace) = trace(GameConfig::classic(3).unwrap(), &[&StandardHost], 3);
+        let valid_line = trace.lines().next().unwrap().to_string();
+        let record: Value = serde_json::from_str(&valid_line).unwrap();
+        let modify = |key: &str, value: Value| {
+            let mut record = record.clone();
+            record[key] = value;
+            record.to_string()
+        };
+        let won = record["won"].as_bool().unwrap();
+        for (line, error) in &[
+            ("{".to_string(), INVALID_TRACE_RECORD_ERROR),
+            (modify("won", json!("yes")), INVALID_TRACE_RECORD_ERROR),
+            (modify("doors", json!(2)), monty_hall::TOO_FEW_DOORS_ERROR),
+            (
+                modify("prize_doors", json!([7])),
+                monty_hall::INVALID_DOOR_ERROR,
+            ),
+            (
+                modify("opened_doors", json!([])),
+                monty_hall::INVALID_HOST_ACTION_ERROR,
+            ),
+            (modify("won", json!(!won)), INCONSISTENT_OUTCOME_ERROR),
+        ] {
+            let lines = vec![valid_line.clone(), String::new(), line.clone()];
+            assert_eq!(
+                Err(format!("Line 3: {}", error)),
+                analyze_lines(&lines).map(|_| ())
+            );
+        }
+    }
+
+    // Opens the listed doors, so any host can be imitated
+    struct FixedHost(Vec<usize>);
+
+    impl Host for FixedHost {
+        fn name(&self) -> String {
+            "fixed".to_string()
+        }
+
+        fn act(
+            &self,
+            _game: &DoorGame,
+            _rng: &mut dyn RngCore,
+            doors_to_open: &mut Vec<usize>,
+        ) -> HostAction {
+            if self.0.is_empty() {
+                return HostAction::NoOffer;
+            }
+            doors_to_open.extend_from_slice(&self.0);
+            HostAction::OfferSwitch
+        }
+    }
+
+    fn create_line(host: &str, host_doors: &[usize], initial_door: usize, prize: usize) -> String {
+        let mut game =
+            DoorGame::with_layout(GameConfig::classic(3).unwrap(), initial_door, &[prize]).unwrap();
+        game.open_doors(&FixedHost(host_doors.to_vec()), &mut StepRng::new(0, 0))
+            .unwrap();
+        create_record(&game, host, "stay", 1, 0).to_string()
+    }
+
+    fn assert_analysis(line: &str, error: Option<&str>) {
+        let lines = vec![line.to_string()];
+        match error {
+            None => assert!(analyze_lines(&lines).is_ok(), "{}", line),
+            Some(error) => assert_eq!(
+                Err(format!("Line 1: {}", error)),
+                analyze_lines(&lines).map(|_| ()),
+                "{}",
+                line
+            ),
+        }
+    }
+
+    #[test]
+    fn host_rules() {
+        for (line, error) in &[
+            // Only the uninformed and the unknown hosts might reveal a prize
+            (create_line("fall", &[2], 0, 2), None),
+            (create_line("lazy", &[2], 0, 2), None),
+            (
+                create_line("standard", &[2], 0, 2),
+                Some(PRIZE_REVEALED_ERROR),
+            ),
+            (create_line("crawl", &[2], 0, 2), Some(PRIZE_REVEALED_ERROR)),
+            (
+                create_line("angelic", &[2], 0, 2),
+                Some(PRIZE_REVEALED_ERROR),
+            ),
+            (create_line("devilish", &[2], 0, 0), None),
+            // The angelic host offers a switch only after a wrong first choice, the devilish one
+            // only after a right one
+            (create_line("angelic", &[2], 0, 1), None),
+            (create_line("angelic", &[], 0, 0), None),
+            (
+                create_line("angelic", &[2], 0, 0),
+                Some(UNEXPECTED_OFFER_ERROR),
+            ),
+            (
+                create_line("angelic", &[], 0, 1),
+                Some(UNEXPECTED_OFFER_ERROR),
+            ),
+            (create_line("devilish", &[], 0, 1), None),
+            (
+                create_line("devilish", &[], 0, 0),
+                Some(UNEXPECTED_OFFER_ERROR),
+            ),
+            (
+                create_line("standard", &[], 0, 1),
+                Some(UNEXPECTED_OFFER_ERROR),
+            ),
+            (
+                create_line("crawl", &[], 0, 1),
+                Some(UNEXPECTED_OFFER_ERROR),
+            ),
+            // The crawling host opens the empty door with the lowest index
+            (create_line("crawl", &[1], 0, 0), None),
+            (create_line("standard", &[2], 0, 0), None),
+            (
+                create_line("crawl", &[2], 0, 0),
+                Some(UNEXPECTED_OPENED_DOORS_ERROR),
+            ),
+        ] {
+            assert_analysis(line, *error);
+        }
+    }
+
+    #[test]
+    fn strategy_rules() {
+        let record: Value = serde_json::from_str(&create_line("standard", &[1], 0, 2)).unwrap();
+        let modify = |strategy: &str, final_door: usize| {
+            let mut record = record.clone();
+            record["strategy"] = json!(strategy);
+            record["final_door"] = json!(final_door);
+            record["won"] = json!(final_door == 2);
+            record.to_string()
+        };
+        for (line, error) in &[
+            (modify("stay", 0), None),
+            (modify("stay", 2), Some(UNEXPECTED_DECISION_ERROR)),
+            (modify("p=0", 2), Some(UNEXPECTED_DECISION_ERROR)),
+            (modify("switch", 2), None),
+            (modify("switch", 0), Some(UNEXPECTED_DECISION_ERROR)),
+            (modify("p=1", 0), Some(UNEXPECTED_DECISION_ERROR)),
+            (modify("random", 0), None),
+            (modify("random", 2), None),
+            (modify("p=0.5", 0), None),
+            (modify("sometimes", 2), None),
+        ] {
+            assert_analysis(line, *error);
+        }
+
+        // Without an offer even the switching strategy keeps the first choice
+        let line = create_line("angelic", &[], 0, 0).replace("\"stay\"", "\"switch\"");
+        assert_analysis(&line, None);
+    }
+}
@@ -0,0 +1,405 @@
+// Python bindings of the minesweeper lib. The Python classes wrap the corresponding types of the
+// lib, the boards can be queried as numpy arrays, which is convenient for training solver agents.
+use minesweeper::{
+    FieldInfo, FieldState, FieldType, FlagResult, Game, GameLevel, GameState, OpenInfo, OpenResult,
+    SizeType, CLOSED_VALUE, FLAGGED_VALUE, MINE_VALUE, VOID_VALUE,
+};
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+fn to_py_err(error: &str) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+#[pyclass(name = "GameLevel", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyGameLevel {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl From<PyGameLevel> for GameLevel {
+    fn from(level: PyGameLevel) -> GameLevel {
+        match level {
+            PyGameLevel::Beginner => GameLevel::Beginner,
+            PyGameLevel::Intermediate => GameLevel::Intermediate,
+            PyGameLevel::Expert => GameLevel::Expert,
+        }
+    }
+}
+
+#[pyclass(name = "GameState", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyGameState {
+    NotStarted,
+    Started,
+    Paused,
+    Won,
+    Lost,
+}
+
+impl From<GameState> for PyGameState {
+    fn from(state: GameState) -> PyGameState {
+        match state {
+            GameState::NotStarted => PyGameState::NotStarted,
+            GameState::Started => PyGameState::Started,
+            GameState::Paused => PyGameState::Paused,
+            GameState::Won => PyGameState::Won,
+            GameState::Lost(_) => PyGameState::Lost,
+        }
+    }
+}
+
+#[pyclass(name = "FieldState", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyFieldState {
+    Closed,
+    Opened,
+    Flagged,
+    Void,
+}
+
+impl From<FieldState> for PyFieldState {
+    fn from(state: FieldState) -> PyFieldState {
+        match state {
+            FieldState::Closed => PyFieldState::Closed,
+            FieldState::Opened => PyFieldState::Opened,
+            FieldState::Flagged => PyFieldState::Flagged,
+            FieldState::Void => PyFieldState::Void,
+        }
+    }
+}
+
+#[pyclass(name = "FieldType", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyFieldType {
+    Empty,
+    Numbered,
+    Mine,
+}
+
+impl From<FieldType> for PyFieldType {
+    fn from(field_type: FieldType) -> PyFieldType {
+        match field_type {
+            FieldType::Empty => PyFieldType::Empty,
+            FieldType::Numbered(_) => PyFieldType::Numbered,
+            FieldType::Mine => PyFieldType::Mine,
+        }
+    }
+}
+
+#[pyclass(name = "FieldInfo", eq, get_all)]
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct PyFieldInfo {
+    state: PyFieldState,
+    field_type: PyFieldType,
+    // The number of the field if it is numbered, otherwise zero
+    value: u8,
+}
+
+impl From<FieldInfo> for PyFieldInfo {
+    fn from(field_info: FieldInfo) -> PyFieldInfo {
+        let value = match field_info.field_type {
+            FieldType::Numbered(value) => value,
+            _ => 0,
+        };
+        PyFieldInfo {
+            state: field_info.state.into(),
+            field_type: field_info.field_type.into(),
+            value,
+        }
+    }
+}
+
+#[pymethods]
+impl PyFieldInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "FieldInfo(state={:?}, field_type={:?}, value={})",
+            self.state, self.field_type, self.value
+        )
+    }
+}
+
+#[pyclass(name = "FlagResult", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyFlagResult {
+    Flagged,
+    FlagRemoved,
+    AlreadyOpened,
+}
+
+impl From<FlagResult> for PyFlagResult {
+    fn from(flag_result: FlagResult) -> PyFlagResult {
+        match flag_result {
+            FlagResult::Flagged => PyFlagResult::Flagged,
+            FlagResult::FlagRemoved => PyFlagResult::FlagRemoved,
+            FlagResult::AlreadyOpened => PyFlagResult::AlreadyOpened,
+        }
+    }
+}
+
+#[pyclass(name = "OpenResult", eq, eq_int)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum PyOpenResult {
+    Ok,
+    IsFlagged,
+    Boom,
+    Winner,
+    TimeIsUp,
+    OutOfMoves,
+}
+
+impl From<OpenResult> for PyOpenResult {
+    fn from(open_result: OpenResult) -> PyOpenResult {
+        match open_result {
+            OpenResult::Ok => PyOpenResult::Ok,
+            OpenResult::IsFlagged => PyOpenResult::IsFlagged,
+            OpenResult::Boom => PyOpenResult::Boom,
+            OpenResult::WINNER => PyOpenResult::Winner,
+            OpenResult::TimeIsUp => PyOpenResult::TimeIsUp,
+            OpenResult::OutOfMoves => PyOpenResult::OutOfMoves,
+        }
+    }
+}
+
+#[pyclass(name = "OpenInfo", get_all)]
+#[derive(Clone, Debug)]
+pub struct PyOpenInfo {
+    result: PyOpenResult,
+    // Maps the coordinates of the newly opened fields to their snapshot values
+    newly_opened_fields: HashMap<(SizeType, SizeType), i8>,
+}
+
+impl From<OpenInfo> for PyOpenInfo {
+    fn from(open_info: OpenInfo) -> PyOpenInfo {
+        PyOpenInfo {
+            result: open_info.result.into(),
+            newly_opened_fields: open_info
+                .newly_opened_fields
+                .into_iter()
+                .map(|(coords, field_type)| (coords, field_type.to_value()))
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyOpenInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "OpenInfo(result={:?}, newly_opened_fields={:?})",
+            self.result, self.newly_opened_fields
+        )
+    }
+}
+
+// The game is guarded by a mutex, because the Python objects can be shared between threads
+#[pyclass(name = "Game")]
+pub struct PyGame {
+    game: Mutex<Game>,
+}
+
+impl PyGame {
+    fn from_game(game: Game) -> PyGame {
+        PyGame {
+            game: Mutex::new(game),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Game> {
+        // The game is never left in an inconsistent state, so the poisoning can be ignored
+        self.game
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn snapshot_array(&self) -> PyResult<Array2<i8>> {
+        let game = self.lock();
+        let mut snapshot = Array2::zeros((game.height() as usize, game.width() as usize));
+        for row in 0..game.height() {
+            for col in 0..game.width() {
+                let field_info = game.get_field_info(row, col).map_err(to_py_err)?;
+                snapshot[[row as usize, col as usize]] = field_info.to_value();
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+#[pymethods]
+impl PyGame {
+    #[new]
+    #[pyo3(signature = (level = PyGameLevel::Beginner, seed = None))]
+    fn new(level: PyGameLevel, seed: Option<u64>) -> PyGame {
+        PyGame::from_game(match seed {
+            Some(seed) => Game::new_with_seed(level.into(), seed),
+            None => Game::new(level.into()),
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (height, width, number_of_mines, seed = None))]
+    fn custom(
+        height: SizeType,
+        width: SizeType,
+        number_of_mines: SizeType,
+        seed: Option<u64>,
+    ) -> PyResult<PyGame> {
+        let game = match seed {
+            Some(seed) => Game::new_custom_with_seed(height, width, number_of_mines, seed),
+            None => Game::new_custom(height, width, number_of_mines),
+        };
+        game.map(PyGame::from_game).map_err(to_py_err)
+    }
+
+    #[getter]
+    fn width(&self) -> SizeType {
+        self.lock().width()
+    }
+
+    #[getter]
+    fn height(&self) -> SizeType {
+        self.lock().height()
+    }
+
+    #[getter]
+    fn state(&self) -> PyGameState {
+        self.lock().get_state().into()
+    }
+
+    // In seconds
+    #[getter]
+    fn elapsed(&self) -> f64 {
+        self.lock().get_elapsed().as_secs_f64()
+    }
+
+    fn open(&self, row: SizeType, col: SizeType) -> PyResult<PyOpenInfo> {
+        let open_info = self.lock().open(row, col).map_err(to_py_err)?;
+        Ok(open_info.into())
+    }
+
+    fn open_neighbors(&self, row: SizeType, col: SizeType) -> PyResult<PyOpenInfo> {
+        let open_info = self.lock().open_neighbors(row, col).map_err(to_py_err)?;
+        Ok(open_info.into())
+    }
+
+    fn toggle_flag(&self, row: SizeType, col: SizeType) -> PyResult<PyFlagResult> {
+        let flag_result = self.lock().toggle_flag(row, col).map_err(to_py_err)?;
+        Ok(flag_result.into())
+    }
+
+    fn pause(&self) -> PyResult<()> {
+        self.lock().pause().map_err(to_py_err)
+    }
+
+    fn resume(&self) -> PyResult<()> {
+        self.lock().resume().map_err(to_py_err)
+    }
+
+    fn field_info(&self, row: SizeType, col: SizeType) -> PyResult<PyFieldInfo> {
+        let field_info = self.lock().get_field_info(row, col).map_err(to_py_err)?;
+        Ok(field_info.into())
+    }
+
+    // Returns a height x width int8 array, the values of the fields are described by the
+    // constants of the module
+    fn snapshot<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<i8>>> {
+        Ok(self.snapshot_array()?.into_pyarray(py))
+    }
+}
+
+#[pymodule]
+fn pyminesweeper(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGameLevel>()?;
+    m.add_class::<PyGameState>()?;
+    m.add_class::<PyFieldState>()?;
+    m.add_class::<PyFieldType>()?;
+    m.add_class::<PyFieldInfo>()?;
+    m.add_class::<PyFlagResult>()?;
+    m.add_class::<PyOpenResult>()?;
+    m.add_class::<PyOpenInfo>()?;
+    m.add_class::<PyGame>()?;
+    m.add("CLOSED", CLOSED_VALUE)?;
+    m.add("FLAGGED", FLAGGED_VALUE)?;
+    m.add("MINE", MINE_VALUE)?;
+    m.add("VOID", VOID_VALUE)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_snapshots() {
+        let game = PyGame::new(PyGameLevel::Expert, Some(42));
+        let other_game = PyGame::custom(16, 30, 99, Some(42)).unwrap();
+        assert_eq!(30, game.width());
+        assert_eq!(16, game.height());
+
+        let open_info = game.open(7, 7).unwrap();
+        let other_open_info = other_game.open(7, 7).unwrap();
+        assert_eq!(open_info.result, other_open_info.result);
+        assert_eq!(
+            open_info.newly_opened_fields,
+            other_open_info.newly_opened_fields
+        );
+        assert_eq!(
+            game.snapshot_array().unwrap(),
+            other_game.snapshot_array().unwrap()
+        );
+    }
+
+    #[test]
+    fn snapshot_values() {
+        let game = PyGame::custom(3, 3, 8, Some(0)).unwrap();
+        assert_eq!(
+            Array2::from_elem((3, 3), CLOSED_VALUE),
+            game.snapshot_array().unwrap()
+        );
+        game.toggle_flag(0, 0).unwrap();
+        let open_info = game.open(1, 1).unwrap();
+        assert_eq!(Some(&8), open_info.newly_opened_fields.get(&(1, 1)));
+
+        let snapshot = game.snapshot_array().unwrap();
+        assert_eq!(&[3, 3], snapshot.shape());
+        assert_eq!(8, snapshot[[1, 1]]);
+        assert_eq!(FLAGGED_VALUE, snapshot[[0, 0]]);
+        assert_eq!(
+            PyFieldInfo {
+                state: PyFieldState::Opened,
+                field_type: PyFieldType::Numbered,
+                value: 8,
+            },
+            game.field_info(1, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn paused_game() {
+        let game = PyGame::new(PyGameLevel::Beginner, None);
+        game.open(0, 0).unwrap();
+        game.pause().unwrap();
+        assert_eq!(PyGameState::Paused, game.state());
+        assert!(game.open(1, 1).is_err());
+        assert!(game
+            .snapshot_array()
+            .unwrap()
+            .iter()
+            .all(|value| *value == CLOSED_VALUE));
+        game.resume().unwrap();
+        assert!(game.resume().is_err());
+    }
+
+    #[test]
+    fn invalid_custom_game() {
+        assert!(PyGame::custom(3, 3, 9, None).is_err());
+        assert!(PyGame::custom(3, 3, 0, Some(1)).is_err());
+    }
+}
@@ -0,0 +1,394 @@
+use minesweeper::{FieldState, FlagResult, Game, GameLevel, GameState, SizeType};
+use ratatui::crossterm::event::{
+    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::Rect;
+
+// Every field is drawn as three characters, e.g. " 1 "
+pub const FIELD_WIDTH: u16 = 3;
+
+pub const LEVEL_OPTIONS: [(&str, Option<GameLevel>); 4] = [
+    ("Beginner", Some(GameLevel::Beginner)),
+    ("Intermediate", Some(GameLevel::Intermediate)),
+    ("Expert", Some(GameLevel::Expert)),
+    ("Custom", None),
+];
+
+pub const CUSTOM_SIZE_LABELS: [&str; 3] = ["Height", "Width", "Mines"];
+
+static INVALID_NUMBER_ERROR: &str = "Invalid number!";
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct CustomSizeDialog {
+    pub values: [String; 3],
+    pub focused: usize,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Screen {
+    Playing,
+    LevelSelection { selected: usize },
+    CustomSize(CustomSizeDialog),
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+enum Action {
+    Open,
+    Flag,
+    OpenNeighbors,
+}
+
+pub struct App {
+    pub game: Game,
+    pub cursor: (SizeType, SizeType),
+    pub screen: Screen,
+    pub message: Option<String>,
+    pub should_quit: bool,
+    // The area of the board on the last drawn frame, it is used to find the clicked field
+    pub board_area: Rect,
+    // The fields are hidden while the game is paused, so the flags are counted on toggling
+    number_of_flags: SizeType,
+}
+
+impl App {
+    pub fn new(game: Game) -> App {
+        App {
+            game,
+            cursor: (0, 0),
+            screen: Screen::Playing,
+            message: None,
+            should_quit: false,
+            board_area: Rect::default(),
+            number_of_flags: 0,
+        }
+    }
+
+    pub fn number_of_flags(&self) -> SizeType {
+        self.number_of_flags
+    }
+
+    // Can be negative if there are more flags than mines
+    pub fn remaining_mines(&self) -> SizeType {
+        self.game.number_of_mines() - self.number_of_flags()
+    }
+
+    pub fn tick(&mut self) {
+        self.game.update_timer();
+    }
+
+    fn start_new_game(&mut self, game: Game) {
+        self.game = game;
+        self.number_of_flags = 0;
+        self.cursor = (0, 0);
+        self.screen = Screen::Playing;
+        self.message = None;
+    }
+
+    fn execute(&mut self, action: Action, row: SizeType, col: SizeType) {
+        let result = match action {
+            Action::Open => self.game.open(row, col).map(|_| ()),
+            Action::Flag => self
+                .game
+                .toggle_flag(row, col)
+                .map(|flag_result| match flag_result {
+                    FlagResult::Flagged => self.number_of_flags += 1,
+                    FlagResult::FlagRemoved => self.number_of_flags -= 1,
+                    FlagResult::AlreadyOpened => (),
+                }),
+            Action::OpenNeighbors => self.game.open_neighbors(row, col).map(|_| ()),
+        };
+        self.message = match (result, self.game.get_state()) {
+            (Err(error), _) => Some(error.to_string()),
+            (Ok(()), GameState::Won) => Some("You won!".to_string()),
+            (Ok(()), GameState::Lost(reason)) => Some(format!("You lost: {}", reason)),
+            (Ok(()), _) => None,
+        };
+    }
+
+    fn toggle_pause(&mut self) {
+        let result = match self.game.get_state() {
+            GameState::Paused => self.game.resume(),
+            _ => self.game.pause(),
+        };
+        self.message = result.err().map(|error| error.to_string());
+    }
+
+    fn move_cursor(&mut self, row_offset: SizeType, col_offset: SizeType) {
+        let (row, col) = self.cursor;
+        self.cursor = (
+            (row + row_offset).max(0).min(self.game.height() - 1),
+            (col + col_offset).max(0).min(self.game.width() - 1),
+        );
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.should_quit = true;
+            return;
+        }
+        match self.screen.clone() {
+            Screen::Playing => self.handle_playing_key(key),
+            Screen::LevelSelection { selected } => self.handle_level_selection_key(key, selected),
+            Screen::CustomSize(dialog) => self.handle_custom_size_key(key, dialog),
+        }
+    }
+
+    fn handle_playing_key(&mut self, key: KeyEvent) {
+        let (row, col) = self.cursor;
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('o') => {
+                self.execute(Action::Open, row, col)
+            }
+            KeyCode::Char('f') => self.execute(Action::Flag, row, col),
+            KeyCode::Char('c') => self.execute(Action::OpenNeighbors, row, col),
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Char('n') => self.screen = Screen::LevelSelection { selected: 0 },
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            _ => (),
+        }
+    }
+
+    fn handle_level_selection_key(&mut self, key: KeyEvent, selected: usize) {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.screen = Screen::LevelSelection {
+                    selected: selected.saturating_sub(1),
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.screen = Screen::LevelSelection {
+                    selected: (selected + 1).min(LEVEL_OPTIONS.len() - 1),
+                }
+            }
+            KeyCode::Enter => match LEVEL_OPTIONS[selected].1 {
+                Some(level) => self.start_new_game(Game::new(level)),
+                None => self.screen = Screen::CustomSize(CustomSizeDialog::default()),
+            },
+            KeyCode::Esc => self.screen = Screen::Playing,
+            _ => (),
+        }
+    }
+
+    fn handle_custom_size_key(&mut self, key: KeyEvent, mut dialog: CustomSizeDialog) {
+        match key.code {
+            KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                dialog.values[dialog.focused].push(digit)
+            }
+            KeyCode::Backspace => {
+                dialog.values[dialog.focused].pop();
+            }
+            KeyCode::Tab | KeyCode::Down => {
+                dialog.focused = (dialog.focused + 1) % CUSTOM_SIZE_LABELS.len()
+            }
+            KeyCode::BackTab | KeyCode::Up => {
+                dialog.focused =
+                    (dialog.focused + CUSTOM_SIZE_LABELS.len() - 1) % CUSTOM_SIZE_LABELS.len()
+            }
+            KeyCode::Enter => {
+                match create_custom_game(&dialog) {
+                    Ok(game) => self.start_new_game(game),
+                    Err(error) => self.message = Some(error.to_string()),
+                }
+                return;
+            }
+            KeyCode::Esc => {
+                self.screen = Screen::LevelSelection {
+                    selected: LEVEL_OPTIONS.len() - 1,
+                };
+                return;
+            }
+            _ => (),
+        }
+        self.screen = Screen::CustomSize(dialog);
+    }
+
+    fn get_field_at(&self, column: u16, row: u16) -> Option<(SizeType, SizeType)> {
+        let area = self.board_area;
+        if column < area.x || row < area.y {
+            return None;
+        }
+        let field_row = (row - area.y) as SizeType;
+        let field_col = ((column - area.x) / FIELD_WIDTH) as SizeType;
+        if field_row < self.game.height() && field_col < self.game.width() {
+            Some((field_row, field_col))
+        } else {
+            None
+        }
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.screen != Screen::Playing {
+            return;
+        }
+        let action = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => Action::Open,
+            MouseEventKind::Down(MouseButton::Right) => Action::Flag,
+            MouseEventKind::Down(MouseButton::Middle) => Action::OpenNeighbors,
+            _ => return,
+        };
+        if let Some((row, col)) = self.get_field_at(mouse.column, mouse.row) {
+            self.cursor = (row, col);
+            // Clicking on an opened field opens its neighbors like a chord click
+            let is_opened = self
+                .game
+                .get_field_info(row, col)
+                .map(|field_info| field_info.state == FieldState::Opened)
+                .unwrap_or(false);
+            if action == Action::Open && is_opened {
+                self.execute(Action::OpenNeighbors, row, col);
+            } else {
+                self.execute(action, row, col);
+            }
+        }
+    }
+}
+
+fn create_custom_game(dialog: &CustomSizeDialog) -> Result<Game, &'static str> {
+    let mut values = [0; 3];
+    for (value, text) in values.iter_mut().zip(dialog.values.iter()) {
+        *value = text.parse::<SizeType>().map_err(|_| INVALID_NUMBER_ERROR)?;
+    }
+    Game::new_custom(values[0], values[1], values[2])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use minesweeper::TOO_MUCH_MINES_ERROR;
+    use ratatui::crossterm::event::KeyEventKind;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn click(app: &mut App, button: MouseButton, column: u16, row: u16) {
+        app.handle_mouse(MouseEvent {
+            kind: MouseEventKind::Down(button),
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+
+    #[test]
+    fn cursor_stays_on_the_board() {
+        let mut app = App::new(Game::new_custom(3, 4, 2).unwrap());
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Left);
+        assert_eq!((0, 0), app.cursor);
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Down);
+            press(&mut app, KeyCode::Char('l'));
+        }
+        assert_eq!((2, 3), app.cursor);
+    }
+
+    #[test]
+    fn flag_with_keyboard() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        assert_eq!(10, app.remaining_mines());
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('f'));
+        assert!(app.game.get_field_info(0, 1).unwrap().state.is_flagged());
+        assert_eq!(9, app.remaining_mines());
+        press(&mut app, KeyCode::Char('f'));
+        assert_eq!(10, app.remaining_mines());
+    }
+
+    #[test]
+    fn pause_with_keyboard() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        press(&mut app, KeyCode::Char('p'));
+        assert!(app.message.is_some());
+        press(&mut app, KeyCode::Char('f'));
+        press(&mut app, KeyCode::Char('p'));
+        assert_eq!(GameState::Paused, app.game.get_state());
+        // The hidden flags are still counted
+        assert_eq!(9, app.remaining_mines());
+        press(&mut app, KeyCode::Char('f'));
+        assert!(app.message.is_some());
+        press(&mut app, KeyCode::Char('p'));
+        assert_eq!(GameState::Started, app.game.get_state());
+        assert_eq!(None, app.message);
+    }
+
+    #[test]
+    fn mouse_clicks() {
+        let mut app = App::new(Game::new_custom(3, 3, 8).unwrap());
+        app.board_area = Rect::new(1, 2, 9, 3);
+        click(&mut app, MouseButton::Right, 0, 2);
+        click(&mut app, MouseButton::Right, 10, 2);
+        assert_eq!(0, app.number_of_flags());
+
+        click(&mut app, MouseButton::Right, 3, 2);
+        assert!(app.game.get_field_info(0, 0).unwrap().state.is_flagged());
+        click(&mut app, MouseButton::Left, 5, 3);
+        assert_eq!((1, 1), app.cursor);
+        assert_eq!(GameState::Won, app.game.get_state());
+        assert_eq!(Some("You won!".to_string()), app.message);
+    }
+
+    #[test]
+    fn select_level() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        app.cursor = (5, 5);
+        press(&mut app, KeyCode::Char('n'));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(Screen::LevelSelection { selected: 2 }, app.screen);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(Screen::Playing, app.screen);
+        assert_eq!((0, 0), app.cursor);
+        assert_eq!(30, app.game.width());
+        assert_eq!(99, app.game.number_of_mines());
+    }
+
+    #[test]
+    fn custom_size() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        press(&mut app, KeyCode::Char('n'));
+        for _ in 0..LEVEL_OPTIONS.len() {
+            press(&mut app, KeyCode::Down);
+        }
+        press(&mut app, KeyCode::Enter);
+        for code in [
+            KeyCode::Char('5'),
+            KeyCode::Tab,
+            KeyCode::Char('7'),
+            KeyCode::Char('x'),
+            KeyCode::Tab,
+            KeyCode::Char('4'),
+            KeyCode::Char('0'),
+        ]
+        .iter()
+        {
+            press(&mut app, *code);
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(Some(TOO_MUCH_MINES_ERROR.to_string()), app.message);
+
+        press(&mut app, KeyCode::Backspace);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(Screen::Playing, app.screen);
+        assert_eq!(5, app.game.height());
+        assert_eq!(7, app.game.width());
+        assert_eq!(4, app.game.number_of_mines());
+    }
+
+    #[test]
+    fn quit() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        app.handle_key(KeyEvent::new_with_kind(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL,
+            KeyEventKind::Press,
+        ));
+        assert!(app.should_quit);
+    }
+}
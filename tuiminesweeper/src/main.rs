@@ -0,0 +1,41 @@
+use minesweeper::{Game, GameLevel};
+use ratatui::crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind,
+};
+use ratatui::crossterm::execute;
+use ratatui::DefaultTerminal;
+use std::io;
+use std::time::Duration;
+
+mod app;
+mod ui;
+
+use app::App;
+
+// The timer is refreshed at least this often
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = App::new(Game::new(GameLevel::Beginner));
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, &mut app))?;
+        if event::poll(TICK_RATE)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key),
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                _ => (),
+            }
+        }
+        app.tick();
+    }
+    Ok(())
+}
+
+fn main() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    execute!(io::stdout(), EnableMouseCapture)?;
+    let result = run(&mut terminal);
+    execute!(io::stdout(), DisableMouseCapture)?;
+    ratatui::restore();
+    result
+}
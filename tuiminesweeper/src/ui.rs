@@ -0,0 +1,232 @@
+use crate::app::{App, Screen, CUSTOM_SIZE_LABELS, FIELD_WIDTH, LEVEL_OPTIONS};
+use minesweeper::{FieldInfo, FieldState, FieldType, GameState};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+const HELP: &str =
+    "arrows/hjkl: move  space: open  f: flag  c: chord  p: pause  n: new game  q: quit";
+
+fn get_number_color(value: u8) -> Color {
+    match value {
+        1 => Color::Blue,
+        2 => Color::Green,
+        3 => Color::Red,
+        4 => Color::Magenta,
+        5 => Color::Yellow,
+        6 => Color::Cyan,
+        7 => Color::White,
+        _ => Color::Gray,
+    }
+}
+
+fn get_field_span(field_info: &FieldInfo) -> Span<'static> {
+    match (field_info.state, field_info.field_type) {
+        (FieldState::Void, _) => Span::raw("   "),
+        (FieldState::Closed, _) => Span::styled(" ■ ", Style::default().fg(Color::DarkGray)),
+        (FieldState::Flagged, _) => Span::styled(
+            " F ",
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        (FieldState::Opened, FieldType::Empty) => Span::raw(" · "),
+        (FieldState::Opened, FieldType::Numbered(value)) => Span::styled(
+            format!(" {} ", value),
+            Style::default()
+                .fg(get_number_color(value))
+                .add_modifier(Modifier::BOLD),
+        ),
+        (FieldState::Opened, FieldType::Mine) => Span::styled(
+            " * ",
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ),
+    }
+}
+
+fn get_state_text(state: GameState) -> String {
+    match state {
+        GameState::NotStarted => "Not started".to_string(),
+        GameState::Started => "Running".to_string(),
+        GameState::Paused => "Paused".to_string(),
+        GameState::Won => "Won".to_string(),
+        GameState::Lost(reason) => format!("Lost ({})", reason),
+    }
+}
+
+fn draw_board(frame: &mut Frame, app: &mut App, area: Rect) {
+    let mut lines = Vec::new();
+    for row in 0..app.game.height() {
+        let mut spans = Vec::new();
+        for col in 0..app.game.width() {
+            let field_info = app
+                .game
+                .get_field_info(row, col)
+                .expect("The fields of the board must be accessible!");
+            let mut span = get_field_span(&field_info);
+            if (row, col) == app.cursor {
+                span = span.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+            spans.push(span);
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let block = Block::default().borders(Borders::ALL).title("Minesweeper");
+    let board_area = Rect {
+        width: (app.game.width() as u16 * FIELD_WIDTH + 2).min(area.width),
+        height: (app.game.height() as u16 + 2).min(area.height),
+        ..area
+    };
+    app.board_area = block.inner(board_area);
+    frame.render_widget(Paragraph::new(lines).block(block), board_area);
+}
+
+fn get_popup_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn draw_level_selection(frame: &mut Frame, selected: usize) {
+    let items: Vec<ListItem> = LEVEL_OPTIONS
+        .iter()
+        .map(|(name, _)| ListItem::new(*name))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("New game"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let area = get_popup_area(frame.area(), 20, LEVEL_OPTIONS.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(Some(selected)),
+    );
+}
+
+fn draw_custom_size(frame: &mut Frame, values: &[String; 3], focused: usize) {
+    let lines: Vec<Line> = CUSTOM_SIZE_LABELS
+        .iter()
+        .zip(values.iter())
+        .enumerate()
+        .map(|(index, (label, value))| {
+            let style = if index == focused {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::raw(format!("{:>7}: ", label)),
+                Span::styled(format!("{:<6}", value), style),
+            ])
+        })
+        .collect();
+    let paragraph =
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Custom game"));
+    let area = get_popup_area(frame.area(), 20, CUSTOM_SIZE_LABELS.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [header_area, board_area, message_area, help_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let header = format!(
+        "Mines: {:>3}  Time: {:>6.1}  {}",
+        app.remaining_mines(),
+        app.game.get_elapsed().as_secs_f64(),
+        get_state_text(app.game.get_state())
+    );
+    frame.render_widget(Paragraph::new(header), header_area);
+    draw_board(frame, app, board_area);
+    if let Some(message) = &app.message {
+        frame.render_widget(
+            Paragraph::new(message.as_str()).style(Style::default().fg(Color::Yellow)),
+            message_area,
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)),
+        help_area,
+    );
+
+    match &app.screen {
+        Screen::Playing => (),
+        Screen::LevelSelection { selected } => draw_level_selection(frame, *selected),
+        Screen::CustomSize(dialog) => draw_custom_size(frame, &dialog.values, dialog.focused),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use minesweeper::{Game, GameLevel};
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Buffer;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::Terminal;
+
+    fn get_line(buffer: &Buffer, row: u16) -> String {
+        (0..buffer.area.width)
+            .map(|col| buffer[(col, row)].symbol())
+            .collect()
+    }
+
+    fn render(app: &mut App, width: u16, height: u16) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        terminal.backend().buffer().clone()
+    }
+
+    #[test]
+    fn draw_new_game() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        let buffer = render(&mut app, 90, 20);
+        assert!(get_line(&buffer, 0).starts_with("Mines:  10  Time:    0.0  Not started"));
+        assert!(get_line(&buffer, 1).starts_with("┌Minesweeper"));
+        assert!(get_line(&buffer, 2).starts_with("│ ■  ■ "));
+        assert_eq!(Rect::new(1, 2, 30, 10), app.board_area);
+        assert!(get_line(&buffer, 19).starts_with("arrows/hjkl"));
+    }
+
+    #[test]
+    fn draw_opened_fields() {
+        let mut app = App::new(Game::new_custom(3, 3, 8).unwrap());
+        // The flags are counted by the app
+        app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE));
+        app.game.open(1, 1).unwrap();
+        app.cursor = (1, 1);
+        let buffer = render(&mut app, 90, 20);
+        assert!(get_line(&buffer, 0).starts_with("Mines:   7"));
+        assert!(get_line(&buffer, 2).starts_with("│ F "));
+        assert!(get_line(&buffer, 3).starts_with("│ ■  8  ■ "));
+        let cursor_cell = &buffer[(5, 3)];
+        assert!(cursor_cell.modifier.contains(Modifier::REVERSED));
+        assert_eq!(Color::Gray, cursor_cell.fg);
+    }
+
+    #[test]
+    fn draw_level_selection_popup() {
+        let mut app = App::new(Game::new(GameLevel::Beginner));
+        app.screen = Screen::LevelSelection { selected: 1 };
+        let buffer = render(&mut app, 90, 20);
+        let lines: Vec<String> = (0..20).map(|row| get_line(&buffer, row)).collect();
+        assert!(lines.iter().any(|line| line.contains("┌New game")));
+        assert!(lines.iter().any(|line| line.contains("Intermediate")));
+    }
+}